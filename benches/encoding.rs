@@ -0,0 +1,125 @@
+//! Benchmarks of the encoding of a single solution, run with `cargo bench`.
+//!
+//! The encoders are built once per strategy, so only the encoding itself is measured. The Permit2
+//! nonce and deadline are set on the solutions, so no RPC call is made while encoding.
+use std::{env, str::FromStr};
+
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use num_bigint::BigUint;
+use tycho_core::{
+    models::{protocol::ProtocolComponent, Chain},
+    Bytes,
+};
+use tycho_execution::encoding::{
+    evm::{encoder_builder::EVMEncoderBuilder, tycho_encoder::EVMTychoEncoder},
+    models::{Solution, Swap},
+    tycho_encoder::TychoEncoder,
+};
+
+const HOPS: [usize; 3] = [1, 3, 10];
+
+const SWAPPER_PK: &str = "0x123456789abcdef123456789abcdef123456789abcdef123456789abcdef1234";
+
+/// WETH, DAI, USDC, USDT, WBTC, LINK, UNI, AAVE, MKR, CRV and COMP on Ethereum.
+const TOKENS: [&str; 11] = [
+    "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2",
+    "0x6b175474e89094c44da98b954eedeac495271d0f",
+    "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48",
+    "0xdac17f958d2ee523a2206206994597c13d831ec7",
+    "0x2260fac5e5542a773aa44fbcfedf7c193bc2c599",
+    "0x514910771af9ca656af840dff83e8264ecf986ca",
+    "0x1f9840a85d5af5bf1d1762f925bdaddc4201f984",
+    "0x7fc66500c84a76ad7e9c93437bfc5ac33e2ddae9",
+    "0x9f8f72aa9304c8b593d555f12ef6589cc3a579a2",
+    "0xd533a949740bb3306d119cc777fa900ba034cd52",
+    "0xc00e94cb662c3520282e6f5717214004a7f26888",
+];
+
+fn token(index: usize) -> Bytes {
+    Bytes::from_str(TOKENS[index]).unwrap()
+}
+
+/// Builds a route of `hops` sequential swaps through the tokens above, on the given protocol.
+fn route(hops: usize, protocol_system: &str) -> Vec<Swap> {
+    (0..hops)
+        .map(|i| {
+            let mut component = ProtocolComponent {
+                id: format!("0x{:040x}", i + 1),
+                protocol_system: protocol_system.to_string(),
+                ..Default::default()
+            };
+            if protocol_system == "uniswap_v4" {
+                component
+                    .static_attributes
+                    .insert("key_lp_fee".into(), Bytes::from("0x0bb8"));
+                component
+                    .static_attributes
+                    .insert("tick_spacing".into(), Bytes::from("0x3c"));
+            }
+            Swap::new(component, token(i), token(i + 1), 0f64)
+        })
+        .collect()
+}
+
+fn solution(swaps: Vec<Swap>) -> Solution {
+    let user = Bytes::from_str("0xcd09f75E2BF2A4d11F3AB23f1389FcC1621c0cc2").unwrap();
+    Solution {
+        sender: user.clone(),
+        receiver: user,
+        given_token: swaps[0].token_in.clone(),
+        given_amount: BigUint::from(1_000_000_000_000_000_000u64),
+        checked_token: swaps[swaps.len() - 1].token_out.clone(),
+        checked_amount: Some(BigUint::from(1_000u32)),
+        router_address: Bytes::from_str("0x3Ede3eCa2a72B3aeCC820E955B36f38437D01395").unwrap(),
+        permit2_nonce: Some(0),
+        permit2_deadline: Some(u32::MAX as u64),
+        swaps,
+        ..Default::default()
+    }
+}
+
+fn bench_strategy(c: &mut Criterion, name: &str, encoder: &EVMTychoEncoder, protocol_system: &str) {
+    let mut group = c.benchmark_group(name);
+    for hops in HOPS {
+        let solution = solution(route(hops, protocol_system));
+        group.bench_with_input(BenchmarkId::from_parameter(hops), &solution, |b, solution| {
+            b.iter_batched(
+                || vec![solution.clone()],
+                |solutions| {
+                    encoder
+                        .encode_router_calldata(solutions)
+                        .unwrap()
+                },
+                BatchSize::SmallInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+fn encoding_benchmarks(c: &mut Criterion) {
+    // The Permit2 manager builds a client on creation, but never uses it with the nonces set
+    if env::var("RPC_URL").is_err() {
+        env::set_var("RPC_URL", "http://localhost:8545");
+    }
+
+    // A direct execution calls a single executor, so the hops are grouped on Uniswap V4
+    let direct_execution = EVMEncoderBuilder::new()
+        .chain(Chain::Ethereum)
+        .initialize_direct_execution()
+        .unwrap()
+        .build()
+        .unwrap();
+    bench_strategy(c, "direct_execution", &direct_execution, "uniswap_v4");
+
+    let split_swap_permit2 = EVMEncoderBuilder::new()
+        .chain(Chain::Ethereum)
+        .initialize_tycho_router_with_permit2(SWAPPER_PK.to_string())
+        .unwrap()
+        .build()
+        .unwrap();
+    bench_strategy(c, "split_swap_permit2", &split_swap_permit2, "uniswap_v2");
+}
+
+criterion_group!(benches, encoding_benchmarks);
+criterion_main!(benches);
@@ -49,6 +49,7 @@ fn main() {
         // Split defines the fraction of the amount to be swapped. A value of 0 indicates 100% of
         // the amount or the total remaining balance.
         split: 0f64,
+        executor_address: None,
     };
 
     // Then we create a solution object with the previous swap
@@ -102,6 +103,7 @@ fn main() {
         token_in: weth.clone(),
         token_out: dai.clone(),
         split: 0.5f64,
+        executor_address: None,
     };
     let swap_weth_wbtc = Swap {
         component: ProtocolComponent {
@@ -114,6 +116,7 @@ fn main() {
         // This represents the remaining 50%, but to avoid any rounding errors we set this to
         // 0 to signify "the remainder of the WETH value". It should still be very close to 50%
         split: 0f64,
+        executor_address: None,
     };
     let swap_dai_usdc = Swap {
         component: ProtocolComponent {
@@ -124,6 +127,7 @@ fn main() {
         token_in: dai.clone(),
         token_out: usdc.clone(),
         split: 0f64,
+        executor_address: None,
     };
     let swap_wbtc_usdc = Swap {
         component: ProtocolComponent {
@@ -134,6 +138,7 @@ fn main() {
         token_in: wbtc.clone(),
         token_out: usdc.clone(),
         split: 0f64,
+        executor_address: None,
     };
     let mut complex_solution = solution.clone();
     complex_solution.swaps = vec![swap_weth_dai, swap_weth_wbtc, swap_dai_usdc, swap_wbtc_usdc];
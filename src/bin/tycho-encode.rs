@@ -1,15 +1,50 @@
-use std::io::{self, Read};
+use std::{
+    collections::HashMap,
+    env, fmt, fs,
+    io::{self, Read},
+    process::ExitCode,
+    str::FromStr,
+};
 
-use clap::{Parser, Subcommand};
+use clap::{Parser, Subcommand, ValueEnum};
+use num_bigint::BigUint;
 use tycho_core::models::Chain;
+#[cfg(feature = "schema")]
+use tycho_execution::encoding::schema::solution_schema;
 use tycho_execution::encoding::{
-    evm::encoder_builder::EVMEncoderBuilder, models::Solution, tycho_encoder::TychoEncoder,
+    errors::EncodingError,
+    evm::{
+        decoder::{decode_router_calldata, describe_transaction, TransactionSummary},
+        encoder_builder::EVMEncoderBuilder,
+        swap_encoder::swap_encoder_registry::SwapEncoderRegistry,
+        tycho_encoder::ENCODING_FORMAT_VERSION,
+        utils::to_checksum_address,
+    },
+    models::{Solution, Transaction},
+    tycho_encoder::TychoEncoder,
 };
 
+const EXIT_CODES_HELP: &str = "Exit codes:
+  0  Success
+  1  Unexpected failure, e.g. the output couldn't be written
+  2  Invalid input: it couldn't be read or parsed, or a validated solution is invalid
+  3  Encoding error: the solution couldn't be encoded";
+
+/// Environment variable the swapper private key is read from if it isn't given as an argument.
+const SWAPPER_PK_ENV_VAR: &str = "TYCHO_SWAPPER_PK";
+
+/// Chains that the encoder has native and wrapped token addresses configured for.
+const SUPPORTED_CHAINS: [&str; 4] = ["ethereum", "base", "arbitrum", "zksync"];
+
+/// Largest number of decimals accepted with `--decimals`. 10^77 is the largest power of ten that
+/// fits in a uint256, so a token with more decimals can't hold a single token unit.
+const MAX_TOKEN_DECIMALS: u32 = 77;
+
 #[derive(Parser)]
 /// Encode swap transactions for the Tycho router
 ///
-/// Reads a JSON object from stdin with the following structure:
+/// Reads a JSON object (or a JSON array of such objects, to encode several solutions at once) from
+/// stdin (or from the file given with `--input`) with the following structure:
 /// ```json
 /// {
 ///     "sender": "0x...",
@@ -33,73 +68,572 @@ use tycho_execution::encoding::{
 ///         },
 ///         "token_in": "0x...",
 ///         "token_out": "0x...",
-///         "split": 0.0
+///         "split": 0.0,
+///         "executor_address": "0x..."
 ///     }],
 ///     "router_address": "0x..."
 /// }
 /// ```
-#[command(author, version, about, long_about = None)]
+///
+/// Amounts are integer strings in base units, unless the decimals of the token are given with
+/// `--decimals`, e.g. `--decimals 0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2=18` to give WETH
+/// amounts as `"1.5"`.
+///
+/// If built with the `yaml` feature, the same structure can be given as YAML with `--format yaml`.
+/// The output is always JSON. Each encoded transaction has a `format_version`, the version of the
+/// calldata layout that produced it.
+#[command(author, version, about, long_about = None, after_help = EXIT_CODES_HELP)]
 pub struct Cli {
     #[command(subcommand)]
     pub command: Commands,
-    #[arg(short, long)]
+    #[arg(short, long, global = true)]
     executors_file_path: Option<String>,
+    /// Executor addresses as a JSON string, with the same structure as the executors file. Can't
+    /// be used together with `--executors-file-path`
+    #[arg(long, global = true, conflicts_with = "executors_file_path")]
+    executors_json: Option<String>,
+    /// Chain to encode the solution for. Supported chains: ethereum, base, arbitrum, zksync
+    #[arg(short, long, global = true, default_value = "ethereum", value_parser = parse_chain)]
+    chain: Chain,
+    /// Path to a file containing the solution(s) to encode. If set, stdin is ignored
+    #[arg(short, long, global = true)]
+    input: Option<String>,
+    /// Format of the input
+    #[arg(short, long, global = true, value_enum, default_value_t = InputFormat::Json)]
+    format: InputFormat,
+    /// Decimals of a token, as `<ADDRESS>=<DECIMALS>`. Can be repeated. The amounts of the listed
+    /// tokens are read in token units, e.g. `"1.5"`, and scaled to base units: `given_amount` with
+    /// the decimals of the given token, `expected_amount` and `checked_amount` with the decimals
+    /// of the checked token. Amounts of other tokens are read as base units. Decimals above 77 are
+    /// rejected
+    #[arg(long = "decimals", global = true, value_parser = parse_token_decimals)]
+    token_decimals: Vec<(tycho_core::Bytes, u32)>,
+    /// Include a `gas_estimate` (an upper bound of the gas used, as a decimal string) for each
+    /// encoded transaction
+    #[arg(long, global = true)]
+    estimate_gas: bool,
+    /// Output addresses as lowercase hex instead of EIP-55 checksummed
+    #[arg(long, global = true)]
+    no_checksum: bool,
+    /// Path to a file to write the JSON output to, truncating it. If set, nothing is printed to
+    /// stdout
+    #[arg(short, long, global = true)]
+    output: Option<String>,
+    /// Output indented JSON instead of compact single-line JSON
+    #[arg(long, global = true)]
+    pretty: bool,
+}
+
+fn parse_chain(chain: &str) -> Result<Chain, String> {
+    Chain::from_str(chain).map_err(|_| {
+        format!("Unknown chain '{}'. Supported chains: {}", chain, SUPPORTED_CHAINS.join(", "))
+    })
+}
+
+fn parse_token_decimals(token_decimals: &str) -> Result<(tycho_core::Bytes, u32), String> {
+    let (token, decimals) = token_decimals
+        .split_once('=')
+        .ok_or_else(|| format!("Expected <ADDRESS>=<DECIMALS>, got '{}'", token_decimals))?;
+    let token = tycho_core::Bytes::from_str(token)
+        .map_err(|_| format!("Invalid token address '{}'", token))?;
+    if token.len() != 20 {
+        return Err(format!("Invalid token address {:?}", token));
+    }
+    let decimals = decimals
+        .parse()
+        .map_err(|_| format!("Invalid decimals '{}'", decimals))?;
+    if decimals > MAX_TOKEN_DECIMALS {
+        return Err(format!("Decimals must be at most {}, got {}", MAX_TOKEN_DECIMALS, decimals));
+    }
+    Ok((token, decimals))
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum InputFormat {
+    Json,
+    /// Requires the `yaml` feature
+    #[cfg(feature = "yaml")]
+    Yaml,
 }
 
 #[derive(Subcommand)]
 pub enum Commands {
     /// Use the Tycho router encoding strategy
     TychoRouter,
-    /// Use the Tycho router encoding strategy with Permit2 approval and token in transfer
+    /// Use the Tycho router encoding strategy with Permit2 approval and token in transfer. The
+    /// swapper private key is read from `--swapper-pk`, `--swapper-pk-file` or the
+    /// `TYCHO_SWAPPER_PK` environment variable, in this order
     TychoRouterPermit2 {
+        /// Private key of the swapper. Prefer `--swapper-pk-file` or `TYCHO_SWAPPER_PK`, since
+        /// command line arguments are visible in the shell history and process listings
         #[arg(short, long)]
-        swapper_pk: String,
+        swapper_pk: Option<String>,
+        /// Path to a file containing the private key of the swapper. Surrounding whitespace is
+        /// ignored
+        #[arg(long, conflicts_with = "swapper_pk")]
+        swapper_pk_file: Option<String>,
     },
     /// Use the direct execution encoding strategy
     DirectExecution,
+    /// Check the solution(s) for structural errors without encoding them. Prints
+    /// `{"valid": true}` or `{"valid": false, "errors": [...]}` and exits with code 2 if any
+    /// solution is invalid
+    Validate,
+    /// Print the JSON Schema of the solution input and exit. No input is read. Requires the
+    /// `schema` feature
+    #[cfg(feature = "schema")]
+    Schema,
+    /// Print the protocol systems that can be encoded on the chain with the executors config, as a
+    /// JSON array sorted by name, and exit. No input is read
+    Protocols,
+    /// Decode transaction(s) produced by this tool and print the route. The input is a
+    /// `{"to", "value", "data", "selector"}` object as output by the encoding commands, an array
+    /// of them, or a hex calldata string. Exits with code 2 if the calldata doesn't match a known
+    /// layout
+    Decode,
+}
+
+/// Errors of the CLI, classified by their exit code.
+enum CliError {
+    /// The input could not be read or parsed, or a solution is invalid.
+    Input(String),
+    /// The solution could not be encoded.
+    Encoding(EncodingError),
+    /// Any other failure, e.g. writing the output.
+    Other(String),
+}
+
+impl CliError {
+    fn exit_code(&self) -> ExitCode {
+        match self {
+            CliError::Other(_) => ExitCode::from(1),
+            CliError::Input(_) => ExitCode::from(2),
+            CliError::Encoding(_) => ExitCode::from(3),
+        }
+    }
 }
 
-fn main() -> Result<(), Box<dyn std::error::Error>> {
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CliError::Input(e) | CliError::Other(e) => write!(f, "{}", e),
+            CliError::Encoding(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl From<EncodingError> for CliError {
+    fn from(err: EncodingError) -> Self {
+        CliError::Encoding(err)
+    }
+}
+
+fn main() -> ExitCode {
     let cli = Cli::parse();
-    let chain = Chain::Ethereum;
+    match run(cli) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            e.exit_code()
+        }
+    }
+}
+
+fn run(cli: Cli) -> Result<(), CliError> {
+    let chain = cli.chain;
+    let output_path = cli.output.as_deref();
+    let pretty = cli.pretty;
+    #[cfg(feature = "schema")]
+    if let Commands::Schema = cli.command {
+        let schema = serde_json::to_string_pretty(&solution_schema())
+            .map_err(|e| CliError::Other(format!("Failed to serialize schema: {}", e)))?;
+        return write_output(schema, output_path);
+    }
+    if let Commands::Protocols = cli.command {
+        let registry = match cli.executors_json {
+            Some(ref executors_json) => SwapEncoderRegistry::from_json(executors_json, chain)?,
+            None => SwapEncoderRegistry::new(cli.executors_file_path, chain)?,
+        };
+        let protocols = serde_json::json!(registry.supported_protocols());
+        return print_output(vec![protocols], false, output_path, pretty);
+    }
 
-    // Read from stdin until EOF
-    let mut buffer = String::new();
-    io::stdin()
-        .read_to_string(&mut buffer)
-        .map_err(|e| format!("Failed to read from stdin: {}", e))?;
+    let buffer = if let Some(ref path) = cli.input {
+        fs::read_to_string(path)
+            .map_err(|e| CliError::Input(format!("Failed to read input file {:?}: {}", path, e)))?
+    } else {
+        // Read from stdin until EOF
+        let mut buffer = String::new();
+        io::stdin()
+            .read_to_string(&mut buffer)
+            .map_err(|e| CliError::Input(format!("Failed to read from stdin: {}", e)))?;
+        buffer
+    };
 
-    if buffer.trim().is_empty() {
-        return Err("No input provided. Expected JSON input on stdin.".into());
+    // Ignore leading whitespace and a byte order mark when detecting the shape of the input
+    let input = buffer
+        .trim_start()
+        .trim_start_matches('\u{feff}')
+        .trim_start();
+    if input.trim_end().is_empty() {
+        return Err(CliError::Input(
+            "No input provided. Expected a solution on stdin or through --input.".to_string(),
+        ));
     }
-    let solution: Solution = serde_json::from_str(&buffer)?;
+    if let Commands::Decode = cli.command {
+        return decode(input, output_path, pretty);
+    }
+    let token_decimals: HashMap<tycho_core::Bytes, u32> = cli.token_decimals.into_iter().collect();
+    let (solutions, is_batch) = parse_solutions(input, cli.format, &token_decimals)?;
 
     let mut builder = EVMEncoderBuilder::new().chain(chain);
 
     if let Some(config_path) = cli.executors_file_path {
         builder = builder.executors_file_path(config_path);
     }
+    if let Some(ref executors_json) = cli.executors_json {
+        builder =
+            builder.swap_encoder_registry(SwapEncoderRegistry::from_json(executors_json, chain)?);
+    }
 
     builder = match cli.command {
+        Commands::Validate => return validate(chain, &solutions, is_batch, output_path, pretty),
         Commands::TychoRouter => builder.initialize_tycho_router()?,
-        Commands::TychoRouterPermit2 { swapper_pk } => {
-            builder.initialize_tycho_router_with_permit2(swapper_pk)?
-        }
+        Commands::TychoRouterPermit2 { swapper_pk, swapper_pk_file } => builder
+            .initialize_tycho_router_with_permit2(resolve_swapper_pk(
+                swapper_pk,
+                swapper_pk_file,
+            )?)?,
         Commands::DirectExecution => builder.initialize_direct_execution()?,
+        Commands::Protocols | Commands::Decode => {
+            unreachable!("The protocols and decode commands return before encoding")
+        }
+        #[cfg(feature = "schema")]
+        Commands::Schema => unreachable!("The schema command returns before encoding"),
     };
     let encoder = builder.build()?;
-    let transactions = encoder.encode_router_calldata(vec![solution])?;
-    let encoded = serde_json::json!({
-        "to": format!("0x{}", hex::encode(&transactions[0].to)),
-        "value": format!("0x{}", hex::encode(transactions[0].value.to_bytes_be())),
-        "data": format!("0x{}", hex::encode(&transactions[0].data)),
-    });
-    // Output the encoded result as JSON to stdout
-    println!(
-        "{}",
-        serde_json::to_string(&encoded)
-            .map_err(|e| format!("Failed to serialize output: {}", e))?
-    );
+    let gas_estimates = if cli.estimate_gas {
+        Some(
+            solutions
+                .iter()
+                .map(|solution| encoder.estimate_gas(solution))
+                .collect::<Result<Vec<u64>, _>>()?,
+        )
+    } else {
+        None
+    };
+    let transactions = encoder.encode_router_calldata(solutions)?;
+    let encoded_transactions = transactions
+        .iter()
+        .enumerate()
+        .map(|(i, transaction)| {
+            let mut encoded = transaction_to_json(transaction, !cli.no_checksum)?;
+            if let Some(ref gas_estimates) = gas_estimates {
+                encoded["gas_estimate"] = serde_json::Value::String(gas_estimates[i].to_string());
+            }
+            Ok(encoded)
+        })
+        .collect::<Result<Vec<serde_json::Value>, CliError>>()?;
+    print_output(encoded_transactions, is_batch, output_path, pretty)
+}
 
+/// Returns the swapper private key given with `--swapper-pk`, read from the file given with
+/// `--swapper-pk-file`, or read from the `TYCHO_SWAPPER_PK` environment variable, in this order.
+fn resolve_swapper_pk(
+    swapper_pk: Option<String>,
+    swapper_pk_file: Option<String>,
+) -> Result<String, CliError> {
+    let swapper_pk = match (swapper_pk, swapper_pk_file) {
+        (Some(swapper_pk), _) => swapper_pk,
+        (None, Some(path)) => fs::read_to_string(&path).map_err(|e| {
+            CliError::Input(format!("Failed to read swapper private key file {:?}: {}", path, e))
+        })?,
+        (None, None) => env::var(SWAPPER_PK_ENV_VAR).map_err(|_| {
+            CliError::Input(format!(
+                "No swapper private key given. Set it with --swapper-pk, --swapper-pk-file or \
+                 the {} environment variable",
+                SWAPPER_PK_ENV_VAR
+            ))
+        })?,
+    };
+    Ok(swapper_pk.trim().to_string())
+}
+
+/// Parses the input as a single solution or an array of solutions. Returns the solutions and
+/// whether the input was an array. The amounts of the tokens in `token_decimals` are scaled from
+/// token units to base units, see `scale_amounts`.
+fn parse_solutions(
+    input: &str,
+    format: InputFormat,
+    token_decimals: &HashMap<tycho_core::Bytes, u32>,
+) -> Result<(Vec<Solution>, bool), CliError> {
+    let parse_error = |e: String| CliError::Input(format!("Failed to parse input: {}", e));
+    if !token_decimals.is_empty() {
+        return parse_solutions_in_token_units(input, format, token_decimals).map_err(parse_error);
+    }
+    match format {
+        InputFormat::Json => {
+            let is_batch = input.starts_with('[');
+            let solutions = if is_batch {
+                serde_json::from_str(input)
+            } else {
+                serde_json::from_str(input).map(|solution| vec![solution])
+            }
+            .map_err(|e| parse_error(e.to_string()))?;
+            Ok((solutions, is_batch))
+        }
+        #[cfg(feature = "yaml")]
+        InputFormat::Yaml => {
+            let value: serde_yaml::Value =
+                serde_yaml::from_str(input).map_err(|e| parse_error(e.to_string()))?;
+            let is_batch = value.is_sequence();
+            let solutions = if is_batch {
+                serde_yaml::from_value(value)
+            } else {
+                serde_yaml::from_value(value).map(|solution| vec![solution])
+            }
+            .map_err(|e| parse_error(e.to_string()))?;
+            Ok((solutions, is_batch))
+        }
+    }
+}
+
+/// Parses the input like `parse_solutions`, scaling the amounts of the tokens in
+/// `token_decimals` before deserializing the solutions.
+fn parse_solutions_in_token_units(
+    input: &str,
+    format: InputFormat,
+    token_decimals: &HashMap<tycho_core::Bytes, u32>,
+) -> Result<(Vec<Solution>, bool), String> {
+    let value: serde_json::Value = match format {
+        InputFormat::Json => serde_json::from_str(input).map_err(|e| e.to_string())?,
+        #[cfg(feature = "yaml")]
+        InputFormat::Yaml => serde_yaml::from_str(input).map_err(|e| e.to_string())?,
+    };
+    let (values, is_batch) = match value {
+        serde_json::Value::Array(values) => (values, true),
+        value => (vec![value], false),
+    };
+    let solutions = values
+        .into_iter()
+        .map(|mut value| {
+            scale_amounts(&mut value, token_decimals)?;
+            serde_json::from_value(value).map_err(|e| e.to_string())
+        })
+        .collect::<Result<Vec<Solution>, String>>()?;
+    Ok((solutions, is_batch))
+}
+
+/// Scales the amounts of a solution from token units to base units, for the tokens with known
+/// decimals. `given_amount` uses the decimals of the given token, `expected_amount` and
+/// `checked_amount` the decimals of the checked token.
+fn scale_amounts(
+    solution: &mut serde_json::Value,
+    token_decimals: &HashMap<tycho_core::Bytes, u32>,
+) -> Result<(), String> {
+    let Some(fields) = solution.as_object_mut() else {
+        return Ok(());
+    };
+    let decimals_of = |field: &str| {
+        fields
+            .get(field)
+            .and_then(|token| token.as_str())
+            .and_then(|token| tycho_core::Bytes::from_str(token).ok())
+            .and_then(|token| token_decimals.get(&token).copied())
+    };
+    let amounts = [
+        ("given_amount", decimals_of("given_token")),
+        ("expected_amount", decimals_of("checked_token")),
+        ("checked_amount", decimals_of("checked_token")),
+    ];
+    for (field, decimals) in amounts {
+        let Some(decimals) = decimals else {
+            continue;
+        };
+        if let Some(amount) = fields.get_mut(field) {
+            if let Some(units) = amount.as_str() {
+                let base_units = parse_token_units(units, decimals)
+                    .map_err(|e| format!("Invalid {}: {}", field, e))?;
+                *amount = serde_json::Value::String(base_units.to_string());
+            }
+        }
+    }
     Ok(())
 }
+
+/// Parses a decimal amount in token units, e.g. `"1.5"`, into base units. The conversion is exact:
+/// amounts with more fractional digits than `decimals` are rejected instead of rounded.
+fn parse_token_units(units: &str, decimals: u32) -> Result<BigUint, String> {
+    let (integer, fraction) = units
+        .split_once('.')
+        .unwrap_or((units, ""));
+    let is_digits = |part: &str| part.chars().all(|c| c.is_ascii_digit());
+    if (integer.is_empty() && fraction.is_empty()) || !is_digits(integer) || !is_digits(fraction) {
+        return Err(format!("'{}' is not a decimal amount", units));
+    }
+    if fraction.len() > decimals as usize {
+        return Err(format!("'{}' has more than {} decimal places", units, decimals));
+    }
+    let padding = "0".repeat(decimals as usize - fraction.len());
+    BigUint::from_str(&format!("0{}{}{}", integer, fraction, padding))
+        .map_err(|e| format!("'{}' is not a decimal amount: {}", units, e))
+}
+
+/// Outputs the results as JSON, indented if `pretty` is set. A single solution produces a single
+/// object, a batch produces an array in input order.
+fn print_output(
+    mut results: Vec<serde_json::Value>,
+    is_batch: bool,
+    output_path: Option<&str>,
+    pretty: bool,
+) -> Result<(), CliError> {
+    let output = if is_batch {
+        serde_json::Value::Array(results)
+    } else {
+        results
+            .pop()
+            .ok_or_else(|| CliError::Other("No result was produced".to_string()))?
+    };
+    let output =
+        if pretty { serde_json::to_string_pretty(&output) } else { serde_json::to_string(&output) }
+            .map_err(|e| CliError::Other(format!("Failed to serialize output: {}", e)))?;
+    write_output(output, output_path)
+}
+
+/// Writes the JSON output to the file at `output_path` (truncating it) if it's set, or to stdout
+/// otherwise.
+fn write_output(output: String, output_path: Option<&str>) -> Result<(), CliError> {
+    match output_path {
+        Some(path) => fs::write(path, output + "\n")
+            .map_err(|e| CliError::Other(format!("Failed to write output file {:?}: {}", path, e))),
+        None => {
+            println!("{}", output);
+            Ok(())
+        }
+    }
+}
+
+fn validate(
+    chain: Chain,
+    solutions: &[Solution],
+    is_batch: bool,
+    output_path: Option<&str>,
+    pretty: bool,
+) -> Result<(), CliError> {
+    let errors: Vec<Vec<String>> = solutions
+        .iter()
+        .map(|solution| match solution.validate(chain) {
+            Ok(()) => vec![],
+            Err(errors) => errors
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+        })
+        .collect();
+    let invalid_count = errors
+        .iter()
+        .filter(|errors| !errors.is_empty())
+        .count();
+    let results = errors
+        .into_iter()
+        .map(|errors| {
+            if errors.is_empty() {
+                serde_json::json!({"valid": true})
+            } else {
+                serde_json::json!({"valid": false, "errors": errors})
+            }
+        })
+        .collect();
+    print_output(results, is_batch, output_path, pretty)?;
+    if invalid_count > 0 {
+        return Err(CliError::Input(format!("{} invalid solution(s)", invalid_count)));
+    }
+    Ok(())
+}
+
+fn decode(input: &str, output_path: Option<&str>, pretty: bool) -> Result<(), CliError> {
+    // Anything that isn't JSON is taken as a hex calldata string
+    let input = serde_json::from_str(input)
+        .unwrap_or_else(|_| serde_json::Value::String(input.trim_end().to_string()));
+    let (transactions, is_batch) = match input {
+        serde_json::Value::Array(transactions) => (transactions, true),
+        transaction => (vec![transaction], false),
+    };
+    let summaries = transactions
+        .iter()
+        .map(|transaction| {
+            let summary = decode_transaction(transaction)
+                .map_err(|e| CliError::Input(format!("Failed to decode transaction: {}", e)))?;
+            serde_json::to_value(summary)
+                .map_err(|e| CliError::Other(format!("Failed to serialize summary: {}", e)))
+        })
+        .collect::<Result<Vec<serde_json::Value>, CliError>>()?;
+    print_output(summaries, is_batch, output_path, pretty)
+}
+
+/// Decodes a transaction given as a hex calldata string or as a JSON object output by the encoding
+/// commands. Without a selector, the calldata is decoded as a router call.
+fn decode_transaction(transaction: &serde_json::Value) -> Result<TransactionSummary, String> {
+    let hex_field = |value: &serde_json::Value, field: &str| -> Result<Vec<u8>, String> {
+        let hex_str = value
+            .as_str()
+            .ok_or_else(|| format!("{} must be a hex string", field))?;
+        hex::decode(hex_str.trim_start_matches("0x"))
+            .map_err(|e| format!("{} is not valid hex: {}", field, e))
+    };
+    match transaction {
+        serde_json::Value::String(_) => decode_router_calldata(&hex_field(transaction, "data")?)
+            .map(|summary| TransactionSummary::TychoRouter(Box::new(summary)))
+            .map_err(|e| e.to_string()),
+        serde_json::Value::Object(fields) => {
+            let data = hex_field(
+                fields
+                    .get("data")
+                    .ok_or("Missing data field")?,
+                "data",
+            )?;
+            let Some(selector) = fields.get("selector") else {
+                return decode_router_calldata(&data)
+                    .map(|summary| TransactionSummary::TychoRouter(Box::new(summary)))
+                    .map_err(|e| e.to_string());
+            };
+            let selector: [u8; 4] = hex_field(selector, "selector")?
+                .try_into()
+                .map_err(|_| "selector must be 4 bytes long".to_string())?;
+            let to = match fields.get("to") {
+                Some(to) => tycho_core::Bytes::from(hex_field(to, "to")?),
+                None => tycho_core::Bytes::default(),
+            };
+            let value = match fields.get("value") {
+                Some(value) => BigUint::from_bytes_be(&hex_field(value, "value")?),
+                None => BigUint::ZERO,
+            };
+            let transaction = Transaction { to, value, data, method: String::new(), selector };
+            describe_transaction(&transaction).map_err(|e| e.to_string())
+        }
+        _ => Err("Expected a transaction object or a hex calldata string".to_string()),
+    }
+}
+
+fn transaction_to_json(
+    transaction: &Transaction,
+    checksum: bool,
+) -> Result<serde_json::Value, EncodingError> {
+    Ok(serde_json::json!({
+        "to": format_address(&transaction.to, checksum)?,
+        "value": format!("0x{}", hex::encode(transaction.value.to_bytes_be())),
+        "data": format!("0x{}", hex::encode(&transaction.data)),
+        "method": transaction.method,
+        "selector": format!("0x{}", hex::encode(transaction.selector)),
+        "format_version": ENCODING_FORMAT_VERSION,
+    }))
+}
+
+/// Formats an address for the output, EIP-55 checksummed unless `checksum` is false.
+fn format_address(address: &tycho_core::Bytes, checksum: bool) -> Result<String, EncodingError> {
+    if checksum {
+        to_checksum_address(address)
+    } else {
+        Ok(format!("0x{}", hex::encode(address)))
+    }
+}
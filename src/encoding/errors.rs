@@ -1,6 +1,8 @@
 use std::io;
 
+use num_bigint::BigUint;
 use thiserror::Error;
+use tycho_core::Bytes;
 
 /// Represents the outer-level, user-facing errors of the tycho-execution encoding package.
 ///
@@ -12,6 +14,14 @@ use thiserror::Error;
 /// - `RecoverableError`: Indicates that the encoding has failed with a recoverable error. Retrying
 ///   at a later time may succeed. It may have failed due to a temporary issue, such as a network
 ///   problem.
+/// - `MissingChain`: The chain was not set on the encoder builder.
+/// - `MissingStrategy`: No strategy was set on the encoder builder.
+/// - `UnknownProtocol`: There is no swap encoder for the given protocol system.
+/// - `InvalidSplit`: The non-remainder splits of a token sum to 100% or more.
+/// - `UnsupportedExactOut`: An exact out solution was given, but only exact in is supported.
+/// - `InvalidAddress`: An address field of the solution is not a valid address.
+/// - `SimulationReverted`: The simulation of an encoded transaction reverted.
+/// - `SimulatedAmountTooLow`: The simulated amount out is below the minimum amount of the solution.
 #[derive(Error, Debug, PartialEq)]
 pub enum EncodingError {
     #[error("Invalid input: {0}")]
@@ -20,6 +30,28 @@ pub enum EncodingError {
     FatalError(String),
     #[error("Recoverable error: {0}")]
     RecoverableError(String),
+    #[error("Fatal error: Please set the chain before setting the strategy")]
+    MissingChain,
+    #[error("Fatal error: Please set the chain and strategy before building the encoder")]
+    MissingStrategy,
+    #[error("Fatal error: Unknown protocol system: {protocol_system}")]
+    UnknownProtocol { protocol_system: String },
+    #[error(
+        "Invalid input: Total of non-remainder splits for token {token:?} must be <100%, got {}%",
+        .sum * 100.0
+    )]
+    InvalidSplit { token: Bytes, sum: f64 },
+    #[error("Fatal error: Currently only exact input solutions are supported")]
+    UnsupportedExactOut,
+    #[error("Invalid input: Invalid {field} address")]
+    InvalidAddress { field: &'static str },
+    #[error(
+        "Recoverable error: Simulation reverted: {}",
+        .reason.as_deref().unwrap_or("unknown reason")
+    )]
+    SimulationReverted { reason: Option<String> },
+    #[error("Invalid input: Simulated amount {simulated} is below the minimum amount {minimum}")]
+    SimulatedAmountTooLow { simulated: BigUint, minimum: BigUint },
 }
 
 impl From<io::Error> for EncodingError {
@@ -1,2 +1,12 @@
 pub mod permit2;
 pub mod protocol_approvals_manager;
+
+/// Amount approved for spending the given token of a solution.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ApprovalAmount {
+    /// The given amount of the solution.
+    Exact,
+    /// The maximum amount: `uint256` for the ERC-20 approval, `uint160` for the Permit2
+    /// allowance.
+    Infinite,
+}
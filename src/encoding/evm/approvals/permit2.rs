@@ -20,10 +20,10 @@ use tycho_core::Bytes;
 use crate::encoding::{
     errors::EncodingError,
     evm::{
-        approvals::protocol_approvals_manager::get_client,
+        approvals::{protocol_approvals_manager::get_client, ApprovalAmount},
         utils::{biguint_to_u256, bytes_to_address, encode_input, get_runtime},
     },
-    models::Chain,
+    models::{Chain, Solution},
 };
 
 /// Struct for managing Permit2 operations, including encoding approvals and fetching allowance
@@ -32,9 +32,10 @@ use crate::encoding::{
 pub struct Permit2 {
     address: Address,
     client: Arc<RootProvider<BoxTransport>>,
-    signer: PrivateKeySigner,
+    signer: Option<PrivateKeySigner>,
     chain_id: u64,
     runtime_handle: Handle,
+    approval_amount: ApprovalAmount,
     // Store the runtime to prevent it from being dropped before use.
     // This is required since tycho-execution does not have a pre-existing runtime.
     // However, if the library is used in a context where a runtime already exists, it is not
@@ -70,25 +71,66 @@ sol! {
 
 impl Permit2 {
     pub fn new(swapper_pk: String, chain: Chain) -> Result<Self, EncodingError> {
+        Self::new_with_address(Some(swapper_pk), chain, None)
+    }
+
+    /// Creates a Permit2 manager that can't sign permits. The permits need to be signed
+    /// externally, using the hash returned by `get_signing_hash`.
+    pub fn new_without_signer(chain: Chain) -> Result<Self, EncodingError> {
+        Self::new_with_address(None, chain, None)
+    }
+
+    /// Creates a Permit2 manager for the Permit2 contract at the given address, or at the chain's
+    /// canonical address if it's not set. Without a swapper private key, permits need to be
+    /// signed externally.
+    pub fn new_with_address(
+        swapper_pk: Option<String>,
+        chain: Chain,
+        address: Option<Bytes>,
+    ) -> Result<Self, EncodingError> {
+        let signer = swapper_pk
+            .map(|swapper_pk| {
+                let pk = B256::from_str(&swapper_pk).map_err(|_| {
+                    EncodingError::FatalError(
+                        "Failed to convert swapper private key to B256".to_string(),
+                    )
+                })?;
+                PrivateKeySigner::from_bytes(&pk).map_err(|_| {
+                    EncodingError::FatalError(
+                        "Failed to create signer from private key".to_string(),
+                    )
+                })
+            })
+            .transpose()?;
+        let address = match address {
+            Some(address) => address,
+            None => chain.permit2_address()?,
+        };
         let (handle, runtime) = get_runtime()?;
         let client = block_in_place(|| handle.block_on(get_client()))?;
-        let pk = B256::from_str(&swapper_pk).map_err(|_| {
-            EncodingError::FatalError("Failed to convert swapper private key to B256".to_string())
-        })?;
-        let signer = PrivateKeySigner::from_bytes(&pk).map_err(|_| {
-            EncodingError::FatalError("Failed to create signer from private key".to_string())
-        })?;
         Ok(Self {
-            address: Address::from_str("0x000000000022D473030F116dDEE9F6B43aC78BA3")
-                .map_err(|_| EncodingError::FatalError("Permit2 address not valid".to_string()))?,
+            address: bytes_to_address(&address)?,
             client,
             runtime_handle: handle,
             signer,
             chain_id: chain.id,
             runtime,
+            approval_amount: ApprovalAmount::Exact,
         })
     }
 
+    /// Sets the amount of the permits created for the solutions, see
+    /// `get_permit_single_for_solution`. Defaults to `ApprovalAmount::Exact`.
+    pub fn with_approval_amount(mut self, approval_amount: ApprovalAmount) -> Self {
+        self.approval_amount = approval_amount;
+        self
+    }
+
+    /// Returns the address of the Permit2 contract.
+    pub fn address(&self) -> Bytes {
+        Bytes::from(self.address.to_vec())
+    }
+
     /// Fetches allowance data for a specific owner, spender, and token.
     fn get_existing_allowance(
         &self,
@@ -124,40 +166,112 @@ impl Permit2 {
             ))),
         }
     }
-    /// Creates permit single and signature
-    pub fn get_permit(
+    /// Creates a permit single for the given token and amount.
+    ///
+    /// If the nonce or the signature deadline are not given, the owner's current Permit2 nonce
+    /// and a deadline 30 minutes from now are used.
+    pub fn get_permit_single(
         &self,
         spender: &Bytes,
         owner: &Bytes,
         token: &Bytes,
         amount: &BigUint,
-    ) -> Result<(PermitSingle, Signature), EncodingError> {
+        nonce: Option<u64>,
+        sig_deadline: Option<u64>,
+    ) -> Result<PermitSingle, EncodingError> {
         let current_time = Utc::now()
             .naive_utc()
             .and_utc()
             .timestamp() as u64;
 
-        let (_, _, nonce) = self.get_existing_allowance(owner, spender, token)?;
+        let sig_deadline = sig_deadline.unwrap_or(current_time + PERMIT_SIG_EXPIRATION);
+        validate_sig_deadline(sig_deadline, current_time)?;
+        let nonce = match nonce {
+            Some(nonce) => {
+                if nonce >= 1 << 48 {
+                    return Err(EncodingError::InvalidInput(format!(
+                        "Permit2 nonce {} does not fit in 48 bits",
+                        nonce
+                    )));
+                }
+                U48::from(nonce)
+            }
+            None => {
+                self.get_existing_allowance(owner, spender, token)?
+                    .2
+            }
+        };
         let expiration = U48::from(current_time + PERMIT_EXPIRATION);
-        let sig_deadline = U256::from(current_time + PERMIT_SIG_EXPIRATION);
+        let sig_deadline = U256::from(sig_deadline);
         let amount = U160::from(biguint_to_u256(amount));
 
         let details = PermitDetails { token: bytes_to_address(token)?, amount, expiration, nonce };
 
-        let permit_single = PermitSingle {
-            details,
-            spender: bytes_to_address(spender)?,
-            sigDeadline: sig_deadline,
-        };
+        Ok(PermitSingle { details, spender: bytes_to_address(spender)?, sigDeadline: sig_deadline })
+    }
 
+    /// Creates the permit single that the Tycho router expects for the solution, without signing
+    /// it: the solution's given token and amount, approved by its sender for its router, with its
+    /// Permit2 nonce and deadline. This is the permit signed when encoding the solution with
+    /// Permit2, so it can be shown to the sender before they sign it. The expiration is 30 days
+    /// from the time of the call.
+    ///
+    /// With `ApprovalAmount::Infinite`, the permit approves the maximum amount instead of the given
+    /// amount.
+    ///
+    /// The router address of the solution must be set.
+    pub fn get_permit_single_for_solution(
+        &self,
+        solution: &Solution,
+    ) -> Result<PermitSingle, EncodingError> {
+        self.get_permit_single(
+            &solution.router_address,
+            &solution.sender,
+            &solution.given_token,
+            &self.get_permit_amount(solution),
+            solution.permit2_nonce,
+            solution.permit2_deadline,
+        )
+    }
+
+    fn get_permit_amount(&self, solution: &Solution) -> BigUint {
+        match self.approval_amount {
+            ApprovalAmount::Exact => solution.given_amount.clone(),
+            ApprovalAmount::Infinite => (BigUint::from(1u8) << 160) - 1u8,
+        }
+    }
+
+    /// Returns the EIP-712 hash of the permit single that needs to be signed by the owner.
+    pub fn get_signing_hash(&self, permit_single: &PermitSingle) -> B256 {
         let domain = eip712_domain! {
             name: "Permit2",
             chain_id: self.chain_id,
             verifying_contract: self.address,
         };
-        let hash = permit_single.eip712_signing_hash(&domain);
-        let signature = self
-            .signer
+        permit_single.eip712_signing_hash(&domain)
+    }
+
+    /// Creates permit single and signature. See `get_permit_single` for the nonce and signature
+    /// deadline defaults.
+    pub fn get_permit(
+        &self,
+        spender: &Bytes,
+        owner: &Bytes,
+        token: &Bytes,
+        amount: &BigUint,
+        nonce: Option<u64>,
+        sig_deadline: Option<u64>,
+    ) -> Result<(PermitSingle, Signature), EncodingError> {
+        let signer = self.signer.as_ref().ok_or_else(|| {
+            EncodingError::FatalError(
+                "No signer configured. Sign the permit2 signing hash externally instead"
+                    .to_string(),
+            )
+        })?;
+        let permit_single =
+            self.get_permit_single(spender, owner, token, amount, nonce, sig_deadline)?;
+        let hash = self.get_signing_hash(&permit_single);
+        let signature = signer
             .sign_hash_sync(&hash)
             .map_err(|e| {
                 EncodingError::FatalError(format!(
@@ -167,6 +281,33 @@ impl Permit2 {
             })?;
         Ok((permit_single, signature))
     }
+
+    /// Creates and signs the permit single of the solution. See `get_permit_single_for_solution`.
+    pub fn get_permit_for_solution(
+        &self,
+        solution: &Solution,
+    ) -> Result<(PermitSingle, Signature), EncodingError> {
+        self.get_permit(
+            &solution.router_address,
+            &solution.sender,
+            &solution.given_token,
+            &self.get_permit_amount(solution),
+            solution.permit2_nonce,
+            solution.permit2_deadline,
+        )
+    }
+}
+
+/// Returns an error if the signature deadline is already in the past at the given time (both in
+/// seconds).
+fn validate_sig_deadline(sig_deadline: u64, now: u64) -> Result<(), EncodingError> {
+    if sig_deadline < now {
+        return Err(EncodingError::InvalidInput(format!(
+            "Permit2 deadline {} is in the past (current time: {})",
+            sig_deadline, now
+        )));
+    }
+    Ok(())
 }
 
 #[cfg(test)]
@@ -246,7 +387,7 @@ mod tests {
         let amount = BigUint::from(1000u64);
 
         let (permit, _) = permit2
-            .get_permit(&spender, &owner, &token, &amount)
+            .get_permit(&spender, &owner, &token, &amount, None, None)
             .unwrap();
 
         let expected_details = PermitDetails {
@@ -267,6 +408,149 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_get_permit_with_nonce_and_deadline() {
+        let private_key =
+            "4c0883a69102937d6231471b5dbb6204fe512961708279feb1be6ae5538da033".to_string();
+        let permit2 = Permit2::new(private_key, eth_chain()).expect("Failed to create Permit2");
+
+        let owner = Bytes::from_str("0x2c6a3cd97c6283b95ac8c5a4459ebb0d5fd404f4").unwrap();
+        let spender = Bytes::from_str("0xba12222222228d8ba445958a75a0704d566bf2c8").unwrap();
+        let token = Bytes::from_str("0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48").unwrap();
+        let amount = BigUint::from(1000u64);
+        let sig_deadline = Utc::now().timestamp() as u64 + 60;
+
+        let (permit, _) = permit2
+            .get_permit(&spender, &owner, &token, &amount, Some(7), Some(sig_deadline))
+            .unwrap();
+
+        assert_eq!(permit.details.nonce, U48::from(7));
+        assert_eq!(permit.sigDeadline, U256::from(sig_deadline));
+    }
+
+    #[test]
+    fn test_get_permit_single_for_solution() {
+        let permit2 = Permit2::new_without_signer(eth_chain()).expect("Failed to create Permit2");
+        let sig_deadline = Utc::now().timestamp() as u64 + 60;
+        let solution = Solution {
+            sender: Bytes::from_str("0x2c6a3cd97c6283b95ac8c5a4459ebb0d5fd404f4").unwrap(),
+            given_token: Bytes::from_str("0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48").unwrap(),
+            given_amount: BigUint::from(1000u64),
+            router_address: Bytes::from_str("0x3Ede3eCa2a72B3aeCC820E955B36f38437D01395").unwrap(),
+            permit2_nonce: Some(3),
+            permit2_deadline: Some(sig_deadline),
+            ..Default::default()
+        };
+
+        let permit = permit2
+            .get_permit_single_for_solution(&solution)
+            .unwrap();
+
+        assert_eq!(
+            permit,
+            permit2
+                .get_permit_single(
+                    &solution.router_address,
+                    &solution.sender,
+                    &solution.given_token,
+                    &solution.given_amount,
+                    Some(3),
+                    Some(sig_deadline),
+                )
+                .unwrap()
+        );
+        assert_eq!(
+            permit.spender,
+            Address::from_str("0x3Ede3eCa2a72B3aeCC820E955B36f38437D01395").unwrap()
+        );
+        assert_eq!(permit.details.amount, U160::from(1000u64));
+        assert_eq!(permit.details.nonce, U48::from(3));
+        assert_eq!(permit.sigDeadline, U256::from(sig_deadline));
+    }
+
+    #[test]
+    fn test_get_permit_single_for_solution_infinite_amount() {
+        let permit2 = Permit2::new_without_signer(eth_chain())
+            .expect("Failed to create Permit2")
+            .with_approval_amount(ApprovalAmount::Infinite);
+        let solution = Solution {
+            sender: Bytes::from_str("0x2c6a3cd97c6283b95ac8c5a4459ebb0d5fd404f4").unwrap(),
+            given_token: Bytes::from_str("0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48").unwrap(),
+            given_amount: BigUint::from(1000u64),
+            router_address: Bytes::from_str("0x3Ede3eCa2a72B3aeCC820E955B36f38437D01395").unwrap(),
+            permit2_nonce: Some(0),
+            ..Default::default()
+        };
+
+        let permit = permit2
+            .get_permit_single_for_solution(&solution)
+            .unwrap();
+
+        assert_eq!(permit.details.amount, U160::MAX);
+    }
+
+    #[test]
+    fn test_validate_sig_deadline() {
+        let now = 1_700_000_000;
+        assert!(validate_sig_deadline(now, now).is_ok());
+        assert!(validate_sig_deadline(now + 1, now).is_ok());
+        assert_eq!(
+            validate_sig_deadline(now - 1, now),
+            Err(EncodingError::InvalidInput(
+                "Permit2 deadline 1699999999 is in the past (current time: 1700000000)".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_new_with_address() {
+        let address = Bytes::from_str("0x0000000000225e31D15943971F47aD3022F714Fa").unwrap();
+        let zksync_chain: Chain = TychoCoreChain::ZkSync.into();
+
+        let permit2 =
+            Permit2::new_with_address(None, zksync_chain.clone(), Some(address.clone())).unwrap();
+
+        assert_eq!(permit2.address(), address);
+        assert!(matches!(
+            Permit2::new_with_address(None, zksync_chain, None),
+            Err(EncodingError::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn test_get_signing_hash_matches_signature() {
+        let private_key =
+            "4c0883a69102937d6231471b5dbb6204fe512961708279feb1be6ae5538da033".to_string();
+        let permit2 = Permit2::new(private_key, eth_chain()).expect("Failed to create Permit2");
+        let external_permit2 =
+            Permit2::new_without_signer(eth_chain()).expect("Failed to create Permit2");
+
+        let owner = Bytes::from_str("0x2c6a3cd97c6283b95ac8c5a4459ebb0d5fd404f4").unwrap();
+        let spender = Bytes::from_str("0xba12222222228d8ba445958a75a0704d566bf2c8").unwrap();
+        let token = Bytes::from_str("0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48").unwrap();
+        let amount = BigUint::from(1000u64);
+
+        let (permit, signature) = permit2
+            .get_permit(&spender, &owner, &token, &amount, None, None)
+            .unwrap();
+        let hash = external_permit2.get_signing_hash(&permit);
+        let recovered = signature
+            .recover_address_from_prehash(&hash)
+            .unwrap();
+
+        assert_eq!(
+            recovered,
+            permit2
+                .signer
+                .as_ref()
+                .unwrap()
+                .address()
+        );
+        assert!(external_permit2
+            .get_permit(&spender, &owner, &token, &amount, None, None)
+            .is_err());
+    }
+
     /// This test actually calls the permit method on the Permit2 contract to verify the encoded
     /// data works. It requires an Anvil fork, so please run with the following command: anvil
     /// --fork-url <RPC-URL> And set up the following env var as RPC_URL=127.0.0.1:8545
@@ -310,7 +594,7 @@ mod tests {
         let spender = Bytes::from_str("0xba12222222228d8ba445958a75a0704d566bf2c8").unwrap();
 
         let (permit, signature) = permit2
-            .get_permit(&spender, &anvil_account, &token, &amount)
+            .get_permit(&spender, &anvil_account, &token, &amount, None, None)
             .unwrap();
         let encoded =
             (bytes_to_address(&anvil_account).unwrap(), permit, signature.as_bytes().to_vec())
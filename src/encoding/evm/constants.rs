@@ -2,6 +2,47 @@ use std::{collections::HashSet, sync::LazyLock};
 
 pub const DEFAULT_EXECUTORS_JSON: &str = include_str!("../../../config/executor_addresses.json");
 
+/// Prefix of the executors config keys of Uniswap V2 forks, e.g. `uniswap_v2_fork:my_fork`. The
+/// pools of the protocol system after the prefix are encoded with `UniswapV2ForkSwapEncoder`.
+pub const UNISWAP_V2_FORK_PREFIX: &str = "uniswap_v2_fork:";
+
+/// Signature of the router method executing the swaps, with the input token transferred from the
+/// sender with `transferFrom`.
+pub const SWAP_SIGNATURE: &str =
+    "swap(uint256,address,address,uint256,bool,bool,uint256,address,bytes)";
+/// Signature of the router method executing the swaps, with the input token transferred from the
+/// sender through a Permit2 approval.
+pub const SWAP_PERMIT2_SIGNATURE: &str = "swapPermit2(uint256,address,address,uint256,bool,bool,uint256,address,((address,uint160,uint48,uint48),address,uint256),bytes,bytes)";
+/// Signature of the executor method called by direct executions.
+pub const DIRECT_EXECUTION_SIGNATURE: &str = "swap(uint256,bytes)";
+
+/// Signature of the ERC-7821 batch execution method, implemented by the contract an EOA delegates
+/// to with EIP-7702.
+pub const EIP7702_BATCH_EXECUTE_SIGNATURE: &str = "execute(bytes32,bytes)";
+/// ERC-7821 execution mode of a batch of calls, reverting if any call reverts.
+pub const EIP7702_BATCH_MODE: [u8; 32] = {
+    let mut mode = [0u8; 32];
+    mode[0] = 0x01;
+    mode
+};
+
+/// Upper bound of the gas consumed by a transaction before any swap is executed: the intrinsic
+/// transaction cost plus the calldata.
+pub const BASE_TRANSACTION_GAS: u64 = 50_000;
+/// Upper bound of the gas consumed by the router logic around the swaps (balance checks, fee and
+/// output token transfers).
+pub const ROUTER_OVERHEAD_GAS: u64 = 60_000;
+/// Upper bound of the gas consumed by a `transferFrom` of the input token to the router.
+pub const TRANSFER_FROM_GAS: u64 = 50_000;
+/// Upper bound of the gas consumed by the Permit2 approval and the input token transfer.
+pub const PERMIT2_GAS: u64 = 100_000;
+/// Upper bound of the gas consumed by wrapping or unwrapping the native token.
+pub const NATIVE_ACTION_GAS: u64 = 40_000;
+
+/// Default upper bound of the slippage of a solution (50%). Higher values are rejected, since they
+/// effectively disable the minimum amount out check.
+pub const DEFAULT_MAX_SLIPPAGE: f64 = 0.5;
+
 /// These protocols support the optimization of grouping swaps.
 ///
 /// This requires special encoding to send call data of multiple swaps to a single executor,
@@ -0,0 +1,340 @@
+use alloy_primitives::{Address, Bytes as AlloyBytes, U256};
+use alloy_sol_types::SolValue;
+use num_bigint::BigUint;
+use serde::Serialize;
+use tycho_core::Bytes;
+
+use crate::encoding::{
+    errors::EncodingError,
+    evm::{
+        approvals::permit2::PermitSingle,
+        constants::{DIRECT_EXECUTION_SIGNATURE, SWAP_PERMIT2_SIGNATURE, SWAP_SIGNATURE},
+        utils::get_function_selector,
+    },
+    models::Transaction,
+    serde_primitives::biguint_string,
+};
+
+/// Length of the header of an encoded swap: token in index (1 byte), token out index (1 byte),
+/// split (3 bytes) and executor address (20 bytes).
+const SWAP_HEADER_LENGTH: usize = 25;
+const MAX_UINT24: f64 = 16_777_215.0;
+
+/// A human-readable description of a transaction encoded by this crate.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(tag = "strategy", rename_all = "snake_case")]
+pub enum TransactionSummary {
+    /// A call to the Tycho router.
+    TychoRouter(Box<RouterCallSummary>),
+    /// A direct call to an executor. The protocol data is specific to the executor.
+    DirectExecution {
+        executor: Bytes,
+        #[serde(with = "biguint_string")]
+        value: BigUint,
+        protocol_data: Bytes,
+    },
+}
+
+/// The arguments of a call to one of the swap methods of the Tycho router.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct RouterCallSummary {
+    /// Name of the called method, e.g. `swap` or `swapPermit2`.
+    pub method: String,
+    #[serde(with = "biguint_string")]
+    pub given_amount: BigUint,
+    pub given_token: Bytes,
+    pub checked_token: Bytes,
+    /// Minimum amount of the checked token the swaps must return for the transaction to succeed.
+    #[serde(with = "biguint_string")]
+    pub min_amount_out: BigUint,
+    pub wrap: bool,
+    pub unwrap: bool,
+    /// Number of tokens of the route, which the swaps refer to by index.
+    pub n_tokens: u64,
+    pub receiver: Bytes,
+    /// The Permit2 approval, only set for `swapPermit2` calls.
+    pub permit: Option<PermitSummary>,
+    pub swaps: Vec<SwapSummary>,
+}
+
+/// The Permit2 approval of a `swapPermit2` call.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct PermitSummary {
+    pub token: Bytes,
+    #[serde(with = "biguint_string")]
+    pub amount: BigUint,
+    pub expiration: u64,
+    pub nonce: u64,
+    pub spender: Bytes,
+    pub sig_deadline: u64,
+    pub signature: Bytes,
+}
+
+/// A swap of a router call.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct SwapSummary {
+    pub token_in_index: u8,
+    pub token_out_index: u8,
+    /// Share of the token in amount sold by this swap (0 means the remainder).
+    pub split: f64,
+    pub executor: Bytes,
+    /// Data passed to the executor, specific to the protocol.
+    pub protocol_data: Bytes,
+}
+
+/// Describes a transaction encoded by this crate, with either a router or a direct execution
+/// strategy. Transactions not produced by this crate are rejected with an `InvalidInput` error.
+pub fn describe_transaction(
+    transaction: &Transaction,
+) -> Result<TransactionSummary, EncodingError> {
+    if transaction.selector == get_function_selector(DIRECT_EXECUTION_SIGNATURE) {
+        return Ok(TransactionSummary::DirectExecution {
+            executor: transaction.to.clone(),
+            value: transaction.value.clone(),
+            protocol_data: Bytes::from(transaction.data.clone()),
+        });
+    }
+    decode_router_calldata(&transaction.data)
+        .map(|summary| TransactionSummary::TychoRouter(Box::new(summary)))
+}
+
+/// Decodes the calldata of a Tycho router call encoded by this crate.
+pub fn decode_router_calldata(calldata: &[u8]) -> Result<RouterCallSummary, EncodingError> {
+    if calldata.len() < 4 {
+        return Err(unknown_layout("the calldata is shorter than a function selector"));
+    }
+    let (selector, args) = calldata.split_at(4);
+    if selector == get_function_selector(SWAP_SIGNATURE) {
+        let (
+            given_amount,
+            given_token,
+            checked_token,
+            min_amount_out,
+            wrap,
+            unwrap,
+            n_tokens,
+            receiver,
+            swaps,
+        ) = <(U256, Address, Address, U256, bool, bool, U256, Address, AlloyBytes)>::abi_decode_params(
+            args, true,
+        )
+        .map_err(|e| unknown_layout(&e.to_string()))?;
+        Ok(RouterCallSummary {
+            method: "swap".to_string(),
+            given_amount: u256_to_biguint(given_amount),
+            given_token: address_to_bytes(given_token),
+            checked_token: address_to_bytes(checked_token),
+            min_amount_out: u256_to_biguint(min_amount_out),
+            wrap,
+            unwrap,
+            n_tokens: u256_to_u64(n_tokens)?,
+            receiver: address_to_bytes(receiver),
+            permit: None,
+            swaps: decode_swaps(&swaps)?,
+        })
+    } else if selector == get_function_selector(SWAP_PERMIT2_SIGNATURE) {
+        let (
+            given_amount,
+            given_token,
+            checked_token,
+            min_amount_out,
+            wrap,
+            unwrap,
+            n_tokens,
+            receiver,
+            permit,
+            signature,
+            swaps,
+        ) = <(
+            U256,
+            Address,
+            Address,
+            U256,
+            bool,
+            bool,
+            U256,
+            Address,
+            PermitSingle,
+            AlloyBytes,
+            AlloyBytes,
+        )>::abi_decode_params(args, true)
+        .map_err(|e| unknown_layout(&e.to_string()))?;
+        Ok(RouterCallSummary {
+            method: "swapPermit2".to_string(),
+            given_amount: u256_to_biguint(given_amount),
+            given_token: address_to_bytes(given_token),
+            checked_token: address_to_bytes(checked_token),
+            min_amount_out: u256_to_biguint(min_amount_out),
+            wrap,
+            unwrap,
+            n_tokens: u256_to_u64(n_tokens)?,
+            receiver: address_to_bytes(receiver),
+            permit: Some(PermitSummary {
+                token: address_to_bytes(permit.details.token),
+                amount: u256_to_biguint(U256::from(permit.details.amount)),
+                expiration: permit.details.expiration.to(),
+                nonce: permit.details.nonce.to(),
+                spender: address_to_bytes(permit.spender),
+                sig_deadline: u256_to_u64(permit.sigDeadline)?,
+                signature: Bytes::from(signature.to_vec()),
+            }),
+            swaps: decode_swaps(&swaps)?,
+        })
+    } else {
+        Err(unknown_layout(&format!("unknown function selector 0x{}", hex::encode(selector))))
+    }
+}
+
+/// Decodes the prefix-length encoded swaps of a router call.
+fn decode_swaps(mut encoded_swaps: &[u8]) -> Result<Vec<SwapSummary>, EncodingError> {
+    let mut swaps = vec![];
+    while !encoded_swaps.is_empty() {
+        if encoded_swaps.len() < 2 {
+            return Err(unknown_layout("truncated swap length"));
+        }
+        let (length, rest) = encoded_swaps.split_at(2);
+        let length = u16::from_be_bytes([length[0], length[1]]) as usize;
+        if length < SWAP_HEADER_LENGTH || rest.len() < length {
+            return Err(unknown_layout("truncated swap"));
+        }
+        let (swap, rest) = rest.split_at(length);
+        swaps.push(SwapSummary {
+            token_in_index: swap[0],
+            token_out_index: swap[1],
+            split: u32::from_be_bytes([0, swap[2], swap[3], swap[4]]) as f64 / MAX_UINT24,
+            executor: Bytes::from(swap[5..SWAP_HEADER_LENGTH].to_vec()),
+            protocol_data: Bytes::from(swap[SWAP_HEADER_LENGTH..].to_vec()),
+        });
+        encoded_swaps = rest;
+    }
+    Ok(swaps)
+}
+
+fn unknown_layout(reason: &str) -> EncodingError {
+    EncodingError::InvalidInput(format!(
+        "The calldata doesn't match a known router layout: {}",
+        reason
+    ))
+}
+
+fn u256_to_biguint(value: U256) -> BigUint {
+    BigUint::from_bytes_be(&value.to_be_bytes::<32>())
+}
+
+fn u256_to_u64(value: U256) -> Result<u64, EncodingError> {
+    value
+        .try_into()
+        .map_err(|_| unknown_layout(&format!("{} does not fit in 64 bits", value)))
+}
+
+fn address_to_bytes(address: Address) -> Bytes {
+    Bytes::from(address.to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use tycho_core::models::{protocol::ProtocolComponent, Chain as TychoCoreChain};
+
+    use super::*;
+    use crate::encoding::{
+        evm::{
+            strategy_encoder::strategy_encoders::SplitSwapStrategyEncoder,
+            swap_encoder::swap_encoder_registry::SwapEncoderRegistry,
+        },
+        models::{Solution, Swap},
+        strategy_encoder::StrategyEncoder,
+    };
+
+    fn weth() -> Bytes {
+        Bytes::from_str("0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2").unwrap()
+    }
+
+    fn dai() -> Bytes {
+        Bytes::from_str("0x6b175474e89094c44da98b954eedeac495271d0f").unwrap()
+    }
+
+    #[test]
+    fn test_decode_router_calldata() {
+        let swap = Swap::new(
+            ProtocolComponent {
+                id: "0xA478c2975Ab1Ea89e8196811F51A7B7Ade33eB11".to_string(),
+                protocol_system: "uniswap_v2".to_string(),
+                ..Default::default()
+            },
+            weth(),
+            dai(),
+            0f64,
+        );
+        let registry = SwapEncoderRegistry::new(None, TychoCoreChain::Ethereum).unwrap();
+        let encoder =
+            SplitSwapStrategyEncoder::new(TychoCoreChain::Ethereum, registry, None).unwrap();
+        let receiver = Bytes::from_str("0xcd09f75e2bf2a4d11f3ab23f1389fcc1621c0cc2").unwrap();
+        let solution = Solution {
+            given_token: weth(),
+            given_amount: BigUint::from_str("1_000000000000000000").unwrap(),
+            checked_token: dai(),
+            checked_amount: Some(BigUint::from_str("2_640_000000000000000000").unwrap()),
+            sender: receiver.clone(),
+            receiver: receiver.clone(),
+            router_address: Bytes::from_str("0x3Ede3eCa2a72B3aeCC820E955B36f38437D01395").unwrap(),
+            swaps: vec![swap],
+            ..Default::default()
+        };
+
+        let (calldata, _) = encoder
+            .encode_strategy(solution)
+            .unwrap();
+        let summary = decode_router_calldata(&calldata).unwrap();
+
+        assert_eq!(summary.method, "swap");
+        assert_eq!(summary.given_amount, BigUint::from_str("1_000000000000000000").unwrap());
+        assert_eq!(summary.given_token, weth());
+        assert_eq!(summary.checked_token, dai());
+        assert_eq!(summary.min_amount_out, BigUint::from_str("2_640_000000000000000000").unwrap());
+        assert!(!summary.wrap && !summary.unwrap);
+        assert_eq!(summary.n_tokens, 2);
+        assert_eq!(summary.receiver, receiver);
+        assert_eq!(summary.permit, None);
+        assert_eq!(summary.swaps.len(), 1);
+        assert_eq!(summary.swaps[0].token_in_index, 0);
+        assert_eq!(summary.swaps[0].token_out_index, 1);
+        assert_eq!(summary.swaps[0].split, 0f64);
+        assert_eq!(
+            summary.swaps[0].executor,
+            Bytes::from_str("0xf6c5be66FFf9DC69962d73da0A617a827c382329").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_decode_router_calldata_unknown_layout() {
+        assert!(matches!(
+            decode_router_calldata(&[0x12, 0x34, 0x56, 0x78, 0x00]),
+            Err(EncodingError::InvalidInput(_))
+        ));
+        let mut truncated = get_function_selector(SWAP_SIGNATURE).to_vec();
+        truncated.extend([0u8; 64]);
+        assert!(matches!(decode_router_calldata(&truncated), Err(EncodingError::InvalidInput(_))));
+    }
+
+    #[test]
+    fn test_describe_direct_execution() {
+        let transaction = Transaction {
+            to: Bytes::from_str("0xf6c5be66FFf9DC69962d73da0A617a827c382329").unwrap(),
+            value: BigUint::ZERO,
+            data: vec![0x12, 0x34],
+            method: "swap".to_string(),
+            selector: get_function_selector(DIRECT_EXECUTION_SIGNATURE),
+        };
+
+        assert_eq!(
+            describe_transaction(&transaction).unwrap(),
+            TransactionSummary::DirectExecution {
+                executor: transaction.to.clone(),
+                value: BigUint::ZERO,
+                protocol_data: Bytes::from("0x1234"),
+            }
+        );
+    }
+}
@@ -1,22 +1,54 @@
-use tycho_core::models::Chain;
+use alloy_primitives::U256;
+use tycho_core::{models::Chain, Bytes};
 
 use crate::encoding::{
     errors::EncodingError,
     evm::{
-        strategy_encoder::strategy_encoders::{ExecutorStrategyEncoder, SplitSwapStrategyEncoder},
+        approvals::ApprovalAmount,
+        constants::DEFAULT_MAX_SLIPPAGE,
+        strategy_encoder::strategy_encoders::{
+            AutoStrategyEncoder, ExecutorStrategyEncoder, SplitSwapStrategyEncoder,
+        },
         swap_encoder::swap_encoder_registry::SwapEncoderRegistry,
         tycho_encoder::EVMTychoEncoder,
     },
     strategy_encoder::StrategyEncoder,
 };
 
+/// Strategy chosen on an `EVMEncoderBuilder`. The strategies of this crate are only built in
+/// `EVMEncoderBuilder::build`, so they use the builder's options regardless of the order in which
+/// the options and the strategy are set.
+#[derive(Clone)]
+enum StrategyChoice {
+    TychoRouter,
+    TychoRouterWithPermit2(String),
+    DirectExecution,
+    Auto(Option<String>),
+    Custom(Box<dyn StrategyEncoder>),
+}
+
 /// Builder pattern for constructing an `EVMTychoEncoder` with customizable options.
 ///
-/// This struct allows setting a chain and strategy encoder before building the final encoder.
+/// This struct allows setting a chain and strategy encoder before building the final encoder. The
+/// strategy is built from the options in `build`, so the options can be set before or after
+/// choosing it. A builder with the shared options set can be cloned to build several encoders from
+/// it.
+#[derive(Clone)]
 pub struct EVMEncoderBuilder {
-    strategy: Option<Box<dyn StrategyEncoder>>,
+    strategy: Option<StrategyChoice>,
     chain: Option<Chain>,
     executors_file_path: Option<String>,
+    router_address: Option<Bytes>,
+    permit2_address: Option<Bytes>,
+    swap_encoder_registry: Option<SwapEncoderRegistry>,
+    swap_grouping: bool,
+    max_slippage: f64,
+    min_out_buffer: U256,
+    approval_amount: Option<ApprovalAmount>,
+    deliver_native_as_weth: bool,
+    native_token_sentinel: Option<Bytes>,
+    eip7702_batch: bool,
+    standard_approval: bool,
 }
 
 impl Default for EVMEncoderBuilder {
@@ -27,7 +59,22 @@ impl Default for EVMEncoderBuilder {
 
 impl EVMEncoderBuilder {
     pub fn new() -> Self {
-        EVMEncoderBuilder { chain: None, strategy: None, executors_file_path: None }
+        EVMEncoderBuilder {
+            chain: None,
+            strategy: None,
+            executors_file_path: None,
+            router_address: None,
+            permit2_address: None,
+            swap_encoder_registry: None,
+            swap_grouping: true,
+            max_slippage: DEFAULT_MAX_SLIPPAGE,
+            min_out_buffer: U256::ZERO,
+            approval_amount: None,
+            deliver_native_as_weth: false,
+            native_token_sentinel: None,
+            eip7702_batch: false,
+            standard_approval: false,
+        }
     }
     pub fn chain(mut self, chain: Chain) -> Self {
         self.chain = Some(chain);
@@ -41,33 +88,115 @@ impl EVMEncoderBuilder {
         self
     }
 
+    /// Sets a pre-built `swap_encoder_registry`, to be shared by the strategies initialized by this
+    /// builder. If it's set, the `executors_file_path` is ignored.
+    pub fn swap_encoder_registry(mut self, swap_encoder_registry: SwapEncoderRegistry) -> Self {
+        self.swap_encoder_registry = Some(swap_encoder_registry);
+        self
+    }
+
+    /// Sets the default `router_address`, used for solutions that don't specify one. The router
+    /// address of a solution always takes precedence over this default.
+    pub fn router_address(mut self, router_address: Bytes) -> Self {
+        self.router_address = Some(router_address);
+        self
+    }
+
+    /// Sets the address of the Permit2 contract, for chains without a canonical deployment or to
+    /// use a custom one.
+    pub fn permit2_address(mut self, permit2_address: Bytes) -> Self {
+        self.permit2_address = Some(permit2_address);
+        self
+    }
+
+    /// Sets whether consecutive swaps on a groupable protocol (e.g. Uniswap V4) are executed as a
+    /// single swap by the Tycho router. Enabled by default.
+    pub fn swap_grouping(mut self, swap_grouping: bool) -> Self {
+        self.swap_grouping = swap_grouping;
+        self
+    }
+
+    /// Sets the upper bound of the slippage of a solution encoded for the Tycho router, 50% by
+    /// default. Solutions with a negative slippage or a slippage above this bound are rejected.
+    pub fn max_slippage(mut self, max_slippage: f64) -> Self {
+        self.max_slippage = max_slippage;
+        self
+    }
+
+    /// Sets an absolute amount (in wei of the checked token) subtracted from the minimum amount out
+    /// of the solutions encoded for the Tycho router, to absorb the rounding of integer-math AMMs.
+    /// It isn't applied to a non-zero checked amount, which is used verbatim. This slightly reduces
+    /// the protection against a bad execution. Defaults to zero, so the
+    /// minimum amount out is unchanged.
+    pub fn min_out_buffer(mut self, min_out_buffer: U256) -> Self {
+        self.min_out_buffer = min_out_buffer;
+        self
+    }
+
+    /// Sets the amount approved for spending the given token of the solutions, in both the ERC-20
+    /// approvals and the Permit2 allowance. If it's not set, the ERC-20 approvals are for the
+    /// maximum amount for Permit2 and for the given amount for the router, and the Permit2
+    /// allowance is for the given amount.
+    pub fn approval_amount(mut self, approval_amount: ApprovalAmount) -> Self {
+        self.approval_amount = Some(approval_amount);
+        self
+    }
+
+    /// Sets whether the native token bought by a solution encoded for the Tycho router is delivered
+    /// as the wrapped token (e.g. WETH), skipping the final unwrap. Use it for receivers that are
+    /// contracts that can't receive the native token. Disabled by default. See
+    /// `SplitSwapStrategyEncoder::with_deliver_native_as_weth`.
+    pub fn deliver_native_as_weth(mut self, deliver_native_as_weth: bool) -> Self {
+        self.deliver_native_as_weth = deliver_native_as_weth;
+        self
+    }
+
+    /// Sets the address that represents the native token in the solutions, e.g.
+    /// `0xeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeee`. If it's not set, the chain's native token
+    /// address is used (the zero address on Ethereum).
+    pub fn native_token_sentinel(mut self, native_token_sentinel: Bytes) -> Self {
+        self.native_token_sentinel = Some(native_token_sentinel);
+        self
+    }
+
+    /// Sets whether each solution is encoded as a single ERC-7821 batch of its approvals and swap,
+    /// for a sender delegated with EIP-7702. Disabled by default. See
+    /// `EVMTychoEncoder::with_eip7702_batch` for details.
+    ///
+    /// Batches need a router strategy: `build` fails with the direct execution strategy.
+    pub fn eip7702_batch(mut self, eip7702_batch: bool) -> Self {
+        self.eip7702_batch = eip7702_batch;
+        self
+    }
+
+    /// Sets whether the ERC-20 approval of each solution, e.g. `approve(router, amount)` for the
+    /// `tycho_router` strategy, is returned as a separate transaction before its swap. Disabled
+    /// by default. See `EVMTychoEncoder::with_standard_approval` for details.
+    pub fn standard_approval(mut self, standard_approval: bool) -> Self {
+        self.standard_approval = standard_approval;
+        self
+    }
+
     /// Sets the `strategy_encoder` manually.
     ///
     /// **Note**: This method should not be used in combination with `tycho_router` or
     /// `direct_execution`.
     pub fn strategy_encoder(mut self, strategy: Box<dyn StrategyEncoder>) -> Self {
-        self.strategy = Some(strategy);
+        self.strategy = Some(StrategyChoice::Custom(strategy));
+        self
+    }
+
+    /// Clears the strategy, keeping every other option (chain, registry, router address, etc.), so
+    /// the builder can be reused to initialize a different strategy.
+    pub fn reset_strategy(mut self) -> Self {
+        self.strategy = None;
         self
     }
 
     /// Shortcut method to initialize a `SplitSwapStrategyEncoder` without any approval nor token in
     /// transfer. **Note**: Should not be used at the same time as `strategy_encoder`.
     pub fn initialize_tycho_router(self) -> Result<Self, EncodingError> {
-        if let Some(chain) = self.chain {
-            let swap_encoder_registry =
-                SwapEncoderRegistry::new(self.executors_file_path.clone(), chain)?;
-            let strategy =
-                Box::new(SplitSwapStrategyEncoder::new(chain, swap_encoder_registry, None)?);
-            Ok(EVMEncoderBuilder {
-                chain: Some(chain),
-                strategy: Some(strategy),
-                executors_file_path: self.executors_file_path,
-            })
-        } else {
-            Err(EncodingError::FatalError(
-                "Please set the chain before setting the tycho router".to_string(),
-            ))
-        }
+        self.with_strategy_choice(StrategyChoice::TychoRouter)
     }
 
     /// Shortcut method to initialize a `SplitSwapStrategyEncoder` with Permit2 approval and token
@@ -76,54 +205,220 @@ impl EVMEncoderBuilder {
         self,
         swapper_pk: String,
     ) -> Result<Self, EncodingError> {
-        if let Some(chain) = self.chain {
-            let swap_encoder_registry =
-                SwapEncoderRegistry::new(self.executors_file_path.clone(), chain)?;
-            let strategy = Box::new(SplitSwapStrategyEncoder::new(
-                chain,
-                swap_encoder_registry,
-                Some(swapper_pk),
-            )?);
-            Ok(EVMEncoderBuilder {
-                chain: Some(chain),
-                strategy: Some(strategy),
-                executors_file_path: self.executors_file_path,
-            })
-        } else {
-            Err(EncodingError::FatalError(
-                "Please set the chain before setting the tycho router".to_string(),
-            ))
-        }
+        self.with_strategy_choice(StrategyChoice::TychoRouterWithPermit2(swapper_pk))
     }
 
     /// Shortcut method to initialize an `ExecutorStrategyEncoder`.
     /// **Note**: Should not be used at the same time as `strategy_encoder`.
     pub fn initialize_direct_execution(self) -> Result<Self, EncodingError> {
-        if let Some(chain) = self.chain {
-            let swap_encoder_registry =
-                SwapEncoderRegistry::new(self.executors_file_path.clone(), chain)?;
-            let strategy = Box::new(ExecutorStrategyEncoder::new(swap_encoder_registry));
-            Ok(EVMEncoderBuilder {
-                chain: Some(chain),
-                strategy: Some(strategy),
-                executors_file_path: self.executors_file_path,
-            })
-        } else {
-            Err(EncodingError::FatalError(
-                "Please set the chain before setting the strategy".to_string(),
-            ))
+        self.with_strategy_choice(StrategyChoice::DirectExecution)
+    }
+
+    /// Shortcut method to initialize an `AutoStrategyEncoder`, which sends single swap solutions
+    /// that need no check of the amount out nor wrapping directly to the executor, and every other
+    /// solution to the Tycho router. If `swapper_pk` is set, every solution goes through the router
+    /// with Permit2 approval and token in transfer. See `AutoStrategyEncoder` for the exact rules.
+    /// The executors are read from the `executors_file_path` (or the `swap_encoder_registry`).
+    /// **Note**: Should not be used at the same time as `strategy_encoder`.
+    pub fn initialize_auto_strategy(
+        self,
+        swapper_pk: Option<String>,
+    ) -> Result<Self, EncodingError> {
+        self.with_strategy_choice(StrategyChoice::Auto(swapper_pk))
+    }
+
+    /// Sets the strategy to build. Returns an error if the chain is not set.
+    fn with_strategy_choice(mut self, strategy: StrategyChoice) -> Result<Self, EncodingError> {
+        if self.chain.is_none() {
+            return Err(EncodingError::MissingChain);
+        }
+        self.strategy = Some(strategy);
+        Ok(self)
+    }
+
+    /// Builds the chosen strategy with the current options.
+    fn build_strategy(
+        &self,
+        chain: Chain,
+        strategy: StrategyChoice,
+    ) -> Result<Box<dyn StrategyEncoder>, EncodingError> {
+        // The Permit2 allowance is exact unless an infinite approval is requested
+        let permit2_approval_amount = self
+            .approval_amount
+            .unwrap_or(ApprovalAmount::Exact);
+        Ok(match strategy {
+            StrategyChoice::Custom(strategy) => strategy,
+            StrategyChoice::TychoRouter => Box::new(
+                SplitSwapStrategyEncoder::new(chain, self.get_swap_encoder_registry(chain)?, None)?
+                    .with_swap_grouping(self.swap_grouping)
+                    .with_max_slippage(self.max_slippage)
+                    .with_min_out_buffer(self.min_out_buffer)
+                    .with_deliver_native_as_weth(self.deliver_native_as_weth),
+            ),
+            StrategyChoice::TychoRouterWithPermit2(swapper_pk) => Box::new(
+                SplitSwapStrategyEncoder::new_with_permit2_address(
+                    chain,
+                    self.get_swap_encoder_registry(chain)?,
+                    Some(swapper_pk),
+                    self.permit2_address.clone(),
+                )?
+                .with_swap_grouping(self.swap_grouping)
+                .with_max_slippage(self.max_slippage)
+                .with_min_out_buffer(self.min_out_buffer)
+                .with_deliver_native_as_weth(self.deliver_native_as_weth)
+                .with_approval_amount(permit2_approval_amount),
+            ),
+            StrategyChoice::DirectExecution => {
+                Box::new(ExecutorStrategyEncoder::new(self.get_swap_encoder_registry(chain)?))
+            }
+            StrategyChoice::Auto(swapper_pk) => Box::new(
+                AutoStrategyEncoder::new(
+                    chain,
+                    self.get_swap_encoder_registry(chain)?,
+                    swapper_pk,
+                    self.permit2_address.clone(),
+                )?
+                .with_swap_grouping(self.swap_grouping)
+                .with_max_slippage(self.max_slippage)
+                .with_min_out_buffer(self.min_out_buffer)
+                .with_deliver_native_as_weth(self.deliver_native_as_weth)
+                .with_approval_amount(permit2_approval_amount),
+            ),
+        })
+    }
+
+    /// Returns the pre-built swap encoder registry if it was set, otherwise builds one from the
+    /// executors file.
+    fn get_swap_encoder_registry(
+        &self,
+        chain: Chain,
+    ) -> Result<SwapEncoderRegistry, EncodingError> {
+        match &self.swap_encoder_registry {
+            Some(swap_encoder_registry) => Ok(swap_encoder_registry.clone()),
+            None => SwapEncoderRegistry::new(self.executors_file_path.clone(), chain),
         }
     }
 
-    /// Builds the `EVMTychoEncoder` instance using the configured chain and strategy.
-    /// Returns an error if either the chain or strategy has not been set.
+    /// Builds the `EVMTychoEncoder` instance using the configured chain and strategy, with the
+    /// options set on the builder. Returns an error if either the chain or strategy has not been
+    /// set, if the strategy can't be built (e.g. with an invalid swapper private key), or if
+    /// EIP-7702 batches are enabled with the direct execution strategy.
     pub fn build(self) -> Result<EVMTychoEncoder, EncodingError> {
-        if let (Some(chain), Some(strategy)) = (self.chain, self.strategy) {
-            EVMTychoEncoder::new(chain, strategy)
-        } else {
-            Err(EncodingError::FatalError(
-                "Please set the chain and strategy before building the encoder".to_string(),
-            ))
+        let chain = self
+            .chain
+            .ok_or(EncodingError::MissingChain)?;
+        let strategy = self
+            .strategy
+            .clone()
+            .ok_or(EncodingError::MissingStrategy)?;
+        if self.eip7702_batch && matches!(strategy, StrategyChoice::DirectExecution) {
+            return Err(EncodingError::InvalidInput(
+                "Direct executions can't be encoded as EIP-7702 batches".to_string(),
+            ));
         }
+        let strategy = self.build_strategy(chain, strategy)?;
+        let mut encoder = EVMTychoEncoder::new(chain, strategy, self.router_address)?
+            .with_eip7702_batch(self.eip7702_batch)
+            .with_standard_approval(self.standard_approval);
+        if let Some(approval_amount) = self.approval_amount {
+            encoder = encoder.with_approval_amount(approval_amount);
+        }
+        Ok(match self.native_token_sentinel {
+            Some(native_token_sentinel) => {
+                encoder.with_native_token_sentinel(native_token_sentinel)
+            }
+            None => encoder,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigUint;
+
+    use super::*;
+    use crate::encoding::models::Solution;
+
+    fn solution_with_slippage(slippage: f64) -> Solution {
+        Solution {
+            expected_amount: Some(BigUint::from(1000u64)),
+            slippage: Some(slippage),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_options_set_after_strategy() {
+        let encoder = EVMEncoderBuilder::new()
+            .chain(Chain::Ethereum)
+            .initialize_tycho_router()
+            .unwrap()
+            .max_slippage(0.1)
+            .min_out_buffer(U256::from(2u64))
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            encoder.min_amount_out(&solution_with_slippage(0.2)),
+            Err(EncodingError::InvalidInput(
+                "Slippage must be between 0 and 0.1, got 0.2".to_string()
+            ))
+        );
+        assert_eq!(
+            encoder
+                .min_amount_out(&solution_with_slippage(0.01))
+                .unwrap(),
+            BigUint::from(988u64)
+        );
+    }
+
+    #[test]
+    fn test_cloned_builder_with_different_options() {
+        let template = EVMEncoderBuilder::new()
+            .chain(Chain::Ethereum)
+            .initialize_tycho_router()
+            .unwrap();
+        let encoder = template.clone().build().unwrap();
+        let encoder_with_buffer = template
+            .min_out_buffer(U256::from(2u64))
+            .build()
+            .unwrap();
+
+        let solution = solution_with_slippage(0.01);
+        assert_eq!(
+            encoder
+                .min_amount_out(&solution)
+                .unwrap(),
+            BigUint::from(990u64)
+        );
+        assert_eq!(
+            encoder_with_buffer
+                .min_amount_out(&solution)
+                .unwrap(),
+            BigUint::from(988u64)
+        );
+    }
+
+    #[test]
+    fn test_reset_strategy() {
+        let builder = EVMEncoderBuilder::new()
+            .chain(Chain::Ethereum)
+            .initialize_direct_execution()
+            .unwrap()
+            .reset_strategy();
+
+        assert!(matches!(builder.build(), Err(EncodingError::MissingStrategy)));
+    }
+
+    #[test]
+    fn test_eip7702_batch_with_direct_execution_fails() {
+        let result = EVMEncoderBuilder::new()
+            .chain(Chain::Ethereum)
+            .initialize_direct_execution()
+            .unwrap()
+            .eip7702_batch(true)
+            .build();
+
+        assert!(matches!(result, Err(EncodingError::InvalidInput(_))));
     }
 }
@@ -1,7 +1,10 @@
 pub mod approvals;
 mod constants;
+pub mod decoder;
 pub mod encoder_builder;
+#[cfg(feature = "simulation")]
+mod simulation;
 pub mod strategy_encoder;
-mod swap_encoder;
+pub mod swap_encoder;
 pub mod tycho_encoder;
 pub mod utils;
@@ -0,0 +1,128 @@
+use alloy::{
+    providers::{Provider, ProviderBuilder},
+    rpc::types::{TransactionInput, TransactionRequest},
+};
+use alloy_primitives::{Bytes as AlloyBytes, TxKind, U256};
+use alloy_sol_types::{decode_revert_reason, SolValue};
+use num_bigint::BigUint;
+use tokio::task::block_in_place;
+
+use crate::encoding::{
+    errors::EncodingError,
+    evm::{
+        tycho_encoder::EVMTychoEncoder,
+        utils::{biguint_to_u256, bytes_to_address, get_runtime},
+    },
+    models::{Solution, Transaction},
+};
+
+impl EVMTychoEncoder {
+    /// Simulates the given router transaction with an `eth_call` from the solution's sender and
+    /// returns the amount out.
+    ///
+    /// Returns an error if the call reverts (with the revert reason, if it can be decoded) or if
+    /// the amount out is below the minimum amount out enforced by the strategy for the solution
+    /// (see `min_amount_out`).
+    ///
+    /// Direct executions can't be simulated, since their data is meant to be passed to an
+    /// executor by another contract.
+    pub fn simulate(
+        &self,
+        solution: &Solution,
+        transaction: &Transaction,
+        rpc_url: &str,
+    ) -> Result<U256, EncodingError> {
+        if !transaction
+            .data
+            .starts_with(&transaction.selector)
+        {
+            return Err(EncodingError::InvalidInput(
+                "Only transactions to the router can be simulated".to_string(),
+            ));
+        }
+        let min_amount_out = self.min_amount_out(solution)?;
+        let tx = TransactionRequest {
+            from: Some(bytes_to_address(&solution.sender)?),
+            to: Some(TxKind::from(bytes_to_address(&transaction.to)?)),
+            value: Some(biguint_to_u256(&transaction.value)),
+            input: TransactionInput {
+                input: Some(AlloyBytes::from(transaction.data.clone())),
+                data: None,
+            },
+            ..Default::default()
+        };
+
+        let (handle, _runtime) = get_runtime()?;
+        let output = block_in_place(|| {
+            handle.block_on(async {
+                let client = ProviderBuilder::new()
+                    .on_builtin(rpc_url)
+                    .await
+                    .map_err(|_| {
+                        EncodingError::FatalError("Failed to build provider".to_string())
+                    })?;
+                client.call(&tx).await.map_err(|err| {
+                    match err
+                        .as_error_resp()
+                        .and_then(|payload| payload.as_revert_data())
+                    {
+                        Some(revert_data) => EncodingError::SimulationReverted {
+                            reason: decode_revert_reason(&revert_data),
+                        },
+                        None => EncodingError::RecoverableError(format!(
+                            "Simulation call failed with error: {:?}",
+                            err
+                        )),
+                    }
+                })
+            })
+        })?;
+
+        let amount_out = U256::abi_decode(&output, true).map_err(|_| {
+            EncodingError::FatalError("Failed to decode the simulated amount out".to_string())
+        })?;
+        let simulated = BigUint::from_bytes_be(&amount_out.to_be_bytes::<32>());
+        if simulated < min_amount_out {
+            return Err(EncodingError::SimulatedAmountTooLow { simulated, minimum: min_amount_out });
+        }
+        Ok(amount_out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tycho_core::{models::Chain as TychoCoreChain, Bytes};
+
+    use super::*;
+    use crate::encoding::evm::{
+        strategy_encoder::strategy_encoders::SplitSwapStrategyEncoder,
+        swap_encoder::swap_encoder_registry::SwapEncoderRegistry, utils::get_function_selector,
+    };
+
+    #[test]
+    fn test_simulate_rejects_invalid_slippage() {
+        let registry = SwapEncoderRegistry::new(None, TychoCoreChain::Ethereum).unwrap();
+        let strategy =
+            SplitSwapStrategyEncoder::new(TychoCoreChain::Ethereum, registry, None).unwrap();
+        let encoder =
+            EVMTychoEncoder::new(TychoCoreChain::Ethereum, Box::new(strategy), None).unwrap();
+        let solution = Solution {
+            expected_amount: Some(BigUint::from(1000u64)),
+            slippage: Some(1.5),
+            ..Default::default()
+        };
+        let selector = get_function_selector("swap(uint256)");
+        let transaction = Transaction {
+            to: Bytes::from([0u8; 20].to_vec()),
+            value: BigUint::ZERO,
+            data: selector.to_vec(),
+            method: "swap".to_string(),
+            selector,
+        };
+
+        // The slippage is validated before the RPC is reached
+        let result = encoder.simulate(&solution, &transaction, "http://127.0.0.1:1");
+
+        assert!(matches!(result, Err(EncodingError::InvalidInput(_))));
+    }
+}
@@ -1,6 +1,11 @@
+use std::str::FromStr;
+
 use tycho_core::Bytes;
 
-use crate::encoding::{evm::constants::GROUPABLE_PROTOCOLS, models::Swap};
+use crate::encoding::{
+    errors::EncodingError, evm::constants::GROUPABLE_PROTOCOLS, models::Swap,
+    swap_encoder::SwapEncoder,
+};
 
 /// Represents a group of swaps that can be encoded into a single swap execution for gas
 /// optimization.
@@ -20,6 +25,23 @@ pub struct SwapGroup {
     pub split: f64,
 }
 
+impl SwapGroup {
+    /// Returns the executor address of the group: the executor address override of its swaps if
+    /// set, or the executor address of the given swap encoder otherwise.
+    pub fn executor_address(&self, swap_encoder: &dyn SwapEncoder) -> Result<Bytes, EncodingError> {
+        match self
+            .swaps
+            .first()
+            .and_then(|swap| swap.executor_address.as_ref())
+        {
+            Some(executor_address) if executor_address.len() == 20 => Ok(executor_address.clone()),
+            Some(_) => Err(EncodingError::InvalidAddress { field: "executor_address" }),
+            None => Bytes::from_str(swap_encoder.executor_address())
+                .map_err(|_| EncodingError::FatalError("Invalid executor address".to_string())),
+        }
+    }
+}
+
 /// Group consecutive swaps which can be encoded into one swap execution for gas optimization.
 ///
 /// An example where this applies is the case of USV4, which uses a PoolManager contract
@@ -30,6 +52,7 @@ pub fn group_swaps(swaps: Vec<Swap>) -> Vec<SwapGroup> {
     let mut last_swap_protocol = "".to_string();
     let mut groupable_protocol;
     let mut last_swap_out_token = Bytes::default();
+    let mut last_swap_executor_address = None;
     for swap in swaps {
         let current_swap_protocol = swap.component.protocol_system.clone();
         groupable_protocol = GROUPABLE_PROTOCOLS.contains(&current_swap_protocol.as_str());
@@ -38,7 +61,14 @@ pub fn group_swaps(swaps: Vec<Swap>) -> Vec<SwapGroup> {
         // so we need to check the last swap's out token as well
         let no_split = swap.split == 0.0 && swap.token_in == last_swap_out_token;
 
-        if current_swap_protocol == last_swap_protocol && groupable_protocol && no_split {
+        // Swaps are only grouped if they are executed by the same executor
+        let same_executor = swap.executor_address == last_swap_executor_address;
+
+        if current_swap_protocol == last_swap_protocol &&
+            groupable_protocol &&
+            no_split &&
+            same_executor
+        {
             // Second or later groupable pool in a sequence of groupable pools. Merge to the
             // current group.
             if let Some(group) = current_group.as_mut() {
@@ -62,6 +92,7 @@ pub fn group_swaps(swaps: Vec<Swap>) -> Vec<SwapGroup> {
         }
         last_swap_protocol = current_swap_protocol;
         last_swap_out_token = swap.token_out.clone();
+        last_swap_executor_address = swap.executor_address.clone();
     }
     if let Some(group) = current_group.as_mut() {
         grouped_swaps.push(group.clone());
@@ -69,6 +100,20 @@ pub fn group_swaps(swaps: Vec<Swap>) -> Vec<SwapGroup> {
     grouped_swaps
 }
 
+/// Puts every swap in its own group, so that each swap is executed separately.
+pub fn ungrouped_swaps(swaps: Vec<Swap>) -> Vec<SwapGroup> {
+    swaps
+        .into_iter()
+        .map(|swap| SwapGroup {
+            input_token: swap.token_in.clone(),
+            output_token: swap.token_out.clone(),
+            protocol_system: swap.component.protocol_system.clone(),
+            split: swap.split,
+            swaps: vec![swap],
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use std::str::FromStr;
@@ -105,6 +150,7 @@ mod tests {
             // This represents the remaining 50%, but to avoid any rounding errors we set this to
             // 0 to signify "the remainder of the WETH value". It should still be very close to 50%
             split: 0f64,
+            executor_address: None,
         };
         let swap_wbtc_usdc = Swap {
             component: ProtocolComponent {
@@ -114,6 +160,7 @@ mod tests {
             token_in: wbtc.clone(),
             token_out: usdc.clone(),
             split: 0f64,
+            executor_address: None,
         };
         let swap_usdc_dai = Swap {
             component: ProtocolComponent {
@@ -123,6 +170,7 @@ mod tests {
             token_in: usdc.clone(),
             token_out: dai.clone(),
             split: 0f64,
+            executor_address: None,
         };
         let grouped_swaps = group_swaps(vec![
             swap_weth_wbtc.clone(),
@@ -151,6 +199,51 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_group_swaps_different_executors() {
+        // The swaps are not grouped, since the second one is executed by a different executor
+        //
+        //   WETH ──(USV4)──> WBTC ───(USV4, patched executor)──> USDC
+
+        let weth = weth();
+        let wbtc = Bytes::from_str("0x2260fac5e5542a773aa44fbcfedf7c193bc2c599").unwrap();
+        let usdc = Bytes::from_str("0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48").unwrap();
+        let swap_weth_wbtc = Swap::new(
+            ProtocolComponent { protocol_system: "uniswap_v4".to_string(), ..Default::default() },
+            weth.clone(),
+            wbtc.clone(),
+            0f64,
+        );
+        let swap_wbtc_usdc = Swap::new(
+            ProtocolComponent { protocol_system: "uniswap_v4".to_string(), ..Default::default() },
+            wbtc.clone(),
+            usdc.clone(),
+            0f64,
+        )
+        .with_executor_address(Bytes::from("0x5615deb798bb3e4dfa0139dfa1b3d433cc23b72f"));
+
+        let grouped_swaps = group_swaps(vec![swap_weth_wbtc.clone(), swap_wbtc_usdc.clone()]);
+
+        assert_eq!(
+            grouped_swaps,
+            vec![
+                SwapGroup {
+                    swaps: vec![swap_weth_wbtc],
+                    input_token: weth,
+                    output_token: wbtc.clone(),
+                    protocol_system: "uniswap_v4".to_string(),
+                    split: 0f64,
+                },
+                SwapGroup {
+                    swaps: vec![swap_wbtc_usdc],
+                    input_token: wbtc,
+                    output_token: usdc,
+                    protocol_system: "uniswap_v4".to_string(),
+                    split: 0f64,
+                }
+            ]
+        );
+    }
     #[test]
     fn test_group_swaps_complex_split() {
         // There is a split in the solution, but it's possible to combine two of the USV4 splits.
@@ -174,6 +267,7 @@ mod tests {
             token_in: wbtc.clone(),
             token_out: weth.clone(),
             split: 0f64,
+            executor_address: None,
         };
         let swap_weth_usdc = Swap {
             component: ProtocolComponent {
@@ -183,6 +277,7 @@ mod tests {
             token_in: weth.clone(),
             token_out: usdc.clone(),
             split: 0.5f64,
+            executor_address: None,
         };
         let swap_weth_dai = Swap {
             component: ProtocolComponent {
@@ -194,6 +289,7 @@ mod tests {
             // This represents the remaining 50%, but to avoid any rounding errors we set this to
             // 0 to signify "the remainder of the WETH value". It should still be very close to 50%
             split: 0f64,
+            executor_address: None,
         };
         let swap_dai_usdc = Swap {
             component: ProtocolComponent {
@@ -203,6 +299,7 @@ mod tests {
             token_in: dai.clone(),
             token_out: usdc.clone(),
             split: 0f64,
+            executor_address: None,
         };
         let grouped_swaps = group_swaps(vec![
             swap_wbtc_weth.clone(),
@@ -261,6 +358,7 @@ mod tests {
             token_in: weth.clone(),
             token_out: wbtc.clone(),
             split: 0.5f64,
+            executor_address: None,
         };
         let swap_wbtc_usdc = Swap {
             component: ProtocolComponent {
@@ -270,6 +368,7 @@ mod tests {
             token_in: wbtc.clone(),
             token_out: usdc.clone(),
             split: 0f64,
+            executor_address: None,
         };
         let swap_weth_dai = Swap {
             component: ProtocolComponent {
@@ -281,6 +380,7 @@ mod tests {
             // This represents the remaining 50%, but to avoid any rounding errors we set this to
             // 0 to signify "the remainder of the WETH value". It should still be very close to 50%
             split: 0f64,
+            executor_address: None,
         };
         let swap_dai_usdc = Swap {
             component: ProtocolComponent {
@@ -290,6 +390,7 @@ mod tests {
             token_in: dai.clone(),
             token_out: usdc.clone(),
             split: 0f64,
+            executor_address: None,
         };
 
         let grouped_swaps = group_swaps(vec![
@@ -319,4 +420,55 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn test_ungrouped_swaps() {
+        //   WETH ──(USV4)──> WBTC ───(USV4)──> USDC
+
+        let weth = weth();
+        let wbtc = Bytes::from_str("0x2260fac5e5542a773aa44fbcfedf7c193bc2c599").unwrap();
+        let usdc = Bytes::from_str("0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48").unwrap();
+
+        let swap_weth_wbtc = Swap {
+            component: ProtocolComponent {
+                protocol_system: "uniswap_v4".to_string(),
+                ..Default::default()
+            },
+            token_in: weth.clone(),
+            token_out: wbtc.clone(),
+            split: 0f64,
+            executor_address: None,
+        };
+        let swap_wbtc_usdc = Swap {
+            component: ProtocolComponent {
+                protocol_system: "uniswap_v4".to_string(),
+                ..Default::default()
+            },
+            token_in: wbtc.clone(),
+            token_out: usdc.clone(),
+            split: 0f64,
+            executor_address: None,
+        };
+        let grouped_swaps = ungrouped_swaps(vec![swap_weth_wbtc.clone(), swap_wbtc_usdc.clone()]);
+
+        assert_eq!(
+            grouped_swaps,
+            vec![
+                SwapGroup {
+                    swaps: vec![swap_weth_wbtc],
+                    input_token: weth,
+                    output_token: wbtc.clone(),
+                    protocol_system: "uniswap_v4".to_string(),
+                    split: 0f64,
+                },
+                SwapGroup {
+                    swaps: vec![swap_wbtc_usdc],
+                    input_token: wbtc,
+                    output_token: usdc,
+                    protocol_system: "uniswap_v4".to_string(),
+                    split: 0f64,
+                }
+            ]
+        );
+    }
 }
@@ -1,3 +1,3 @@
 mod group_swaps;
 pub mod strategy_encoders;
-mod strategy_validators;
+pub mod strategy_validators;
@@ -1,21 +1,33 @@
-use std::{collections::HashSet, str::FromStr};
+use std::collections::HashSet;
 
 use alloy_primitives::{aliases::U24, U256, U8};
 use alloy_sol_types::SolValue;
+use num_bigint::BigUint;
 use tycho_core::Bytes;
 
 use crate::encoding::{
     errors::EncodingError,
     evm::{
-        approvals::permit2::Permit2,
-        strategy_encoder::{group_swaps::group_swaps, strategy_validators::SplitSwapValidator},
+        approvals::{
+            permit2::{Permit2, PermitSingle},
+            ApprovalAmount,
+        },
+        constants::{
+            BASE_TRANSACTION_GAS, DEFAULT_MAX_SLIPPAGE, DIRECT_EXECUTION_SIGNATURE,
+            NATIVE_ACTION_GAS, PERMIT2_GAS, ROUTER_OVERHEAD_GAS, SWAP_PERMIT2_SIGNATURE,
+            SWAP_SIGNATURE, TRANSFER_FROM_GAS,
+        },
+        strategy_encoder::{
+            group_swaps::{group_swaps, ungrouped_swaps},
+            strategy_validators::SplitSwapValidator,
+        },
         swap_encoder::swap_encoder_registry::SwapEncoderRegistry,
         utils::{
             biguint_to_u256, bytes_to_address, encode_input, get_min_amount_for_solution,
             get_token_position, percentage_to_uint24,
         },
     },
-    models::{Chain, EncodingContext, NativeAction, Solution},
+    models::{Chain, EncodingContext, NativeAction, Solution, Swap},
     strategy_encoder::StrategyEncoder,
     swap_encoder::SwapEncoder,
 };
@@ -55,6 +67,23 @@ pub trait EVMStrategyEncoder: StrategyEncoder {
 
         encoded_action_data
     }
+
+    /// Sums the gas estimates of the swap encoders used by each of the given swaps.
+    fn estimate_swaps_gas(&self, swaps: &[Swap]) -> Result<u64, EncodingError> {
+        swaps
+            .iter()
+            .try_fold(0u64, |gas, swap| {
+                let swap_encoder = self
+                    .get_swap_encoder(&swap.component.protocol_system)
+                    .ok_or_else(|| {
+                        EncodingError::InvalidInput(format!(
+                            "Swap encoder not found for protocol: {}",
+                            swap.component.protocol_system
+                        ))
+                    })?;
+                Ok(gas + swap_encoder.gas_estimate())
+            })
+    }
 }
 
 /// Represents the encoder for a swap strategy which supports single, sequential and split swaps.
@@ -68,6 +97,12 @@ pub trait EVMStrategyEncoder: StrategyEncoder {
 /// * `wrapped_address`: Address of the chain's wrapped token
 /// * `split_swap_validator`: SplitSwapValidator, responsible for checking validity of split swap
 ///   solutions
+/// * `swap_grouping`: Whether consecutive swaps on groupable protocols are executed as one swap
+/// * `max_slippage`: Upper bound of the slippage of a solution
+/// * `min_out_buffer`: Amount (in wei) subtracted from the slippage-derived minimum amount out of a
+///   solution
+/// * `deliver_native_as_weth`: Whether the native token bought by a solution is delivered as the
+///   wrapped token instead of being unwrapped
 #[derive(Clone)]
 pub struct SplitSwapStrategyEncoder {
     swap_encoder_registry: SwapEncoderRegistry,
@@ -76,6 +111,10 @@ pub struct SplitSwapStrategyEncoder {
     native_address: Bytes,
     wrapped_address: Bytes,
     split_swap_validator: SplitSwapValidator,
+    swap_grouping: bool,
+    max_slippage: f64,
+    min_out_buffer: U256,
+    deliver_native_as_weth: bool,
 }
 
 impl SplitSwapStrategyEncoder {
@@ -83,34 +122,192 @@ impl SplitSwapStrategyEncoder {
         blockchain: tycho_core::models::Chain,
         swap_encoder_registry: SwapEncoderRegistry,
         swapper_pk: Option<String>,
+    ) -> Result<Self, EncodingError> {
+        Self::new_with_permit2_address(blockchain, swap_encoder_registry, swapper_pk, None)
+    }
+
+    /// Creates an encoder that uses the Permit2 contract at `permit2_address` instead of the
+    /// chain's canonical one. The address is ignored if `swapper_pk` is not set.
+    pub fn new_with_permit2_address(
+        blockchain: tycho_core::models::Chain,
+        swap_encoder_registry: SwapEncoderRegistry,
+        swapper_pk: Option<String>,
+        permit2_address: Option<Bytes>,
+    ) -> Result<Self, EncodingError> {
+        let chain = Chain::from(blockchain);
+        let permit2 = swapper_pk
+            .map(|swapper_pk| {
+                Permit2::new_with_address(Some(swapper_pk), chain.clone(), permit2_address)
+            })
+            .transpose()?;
+        Self::new_with_permit2(chain, swap_encoder_registry, permit2)
+    }
+
+    /// Creates an encoder that uses Permit2 without holding the swapper's private key.
+    ///
+    /// The permit has to be signed externally: get the hash to sign with `permit2_digest` and
+    /// encode the solution with `encode_strategy_with_permit2_signature`. If `permit2_address` is
+    /// not set, the chain's canonical Permit2 contract is used.
+    pub fn new_with_external_signer(
+        blockchain: tycho_core::models::Chain,
+        swap_encoder_registry: SwapEncoderRegistry,
+        permit2_address: Option<Bytes>,
     ) -> Result<Self, EncodingError> {
         let chain = Chain::from(blockchain);
-        let (permit2, selector) = if let Some(swapper_pk) = swapper_pk {
-            (Some(Permit2::new(swapper_pk, chain.clone())?), "swapPermit2(uint256,address,address,uint256,bool,bool,uint256,address,((address,uint160,uint48,uint48),address,uint256),bytes,bytes)".to_string())
+        let permit2 = Permit2::new_with_address(None, chain.clone(), permit2_address)?;
+        Self::new_with_permit2(chain, swap_encoder_registry, Some(permit2))
+    }
+
+    fn new_with_permit2(
+        chain: Chain,
+        swap_encoder_registry: SwapEncoderRegistry,
+        permit2: Option<Permit2>,
+    ) -> Result<Self, EncodingError> {
+        let selector = if permit2.is_some() {
+            SWAP_PERMIT2_SIGNATURE.to_string()
         } else {
-            (
-                None,
-                "swap(uint256,address,address,uint256,bool,bool,uint256,address,bytes)".to_string(),
-            )
+            SWAP_SIGNATURE.to_string()
         };
+        let (native_address, wrapped_address) = chain.native_tokens()?;
         Ok(Self {
             permit2,
             selector,
             swap_encoder_registry,
-            native_address: chain.native_token()?,
-            wrapped_address: chain.wrapped_token()?,
+            native_address,
+            wrapped_address,
             split_swap_validator: SplitSwapValidator,
+            swap_grouping: true,
+            max_slippage: DEFAULT_MAX_SLIPPAGE,
+            min_out_buffer: U256::ZERO,
+            deliver_native_as_weth: false,
         })
     }
-}
-impl EVMStrategyEncoder for SplitSwapStrategyEncoder {}
 
-impl StrategyEncoder for SplitSwapStrategyEncoder {
-    fn encode_strategy(&self, solution: Solution) -> Result<(Vec<u8>, Bytes), EncodingError> {
+    /// Sets whether consecutive swaps on a groupable protocol (e.g. Uniswap V4) are merged into a
+    /// single swap execution. Grouping is enabled by default; disable it to execute every swap
+    /// separately.
+    pub fn with_swap_grouping(mut self, swap_grouping: bool) -> Self {
+        self.swap_grouping = swap_grouping;
+        self
+    }
+
+    /// Sets the upper bound of the slippage of a solution, 50% by default. Solutions with a
+    /// negative slippage or a slippage above this bound are rejected.
+    pub fn with_max_slippage(mut self, max_slippage: f64) -> Self {
+        self.max_slippage = max_slippage;
+        self
+    }
+
+    /// Sets whether the solutions that unwrap the wrapped token into the native token deliver the
+    /// wrapped token to the receiver instead, e.g. for receivers that are contracts without a
+    /// payable fallback. Disabled by default.
+    ///
+    /// When enabled, the unwrap is skipped and the wrapped token becomes the checked token, so the
+    /// router checks the minimum amount out (unchanged, since the unwrap is 1:1) against it and
+    /// transfers it to the receiver. Swaps that output the native token directly (e.g. on
+    /// Uniswap V4) still deliver the native token.
+    pub fn with_deliver_native_as_weth(mut self, deliver_native_as_weth: bool) -> Self {
+        self.deliver_native_as_weth = deliver_native_as_weth;
+        self
+    }
+
+    /// Sets the amount of the Permit2 allowance signed for each solution. Defaults to
+    /// `ApprovalAmount::Exact`. It has no effect if Permit2 is not enabled.
+    pub fn with_approval_amount(mut self, approval_amount: ApprovalAmount) -> Self {
+        self.permit2 = self
+            .permit2
+            .map(|permit2| permit2.with_approval_amount(approval_amount));
+        self
+    }
+
+    /// Sets an absolute amount (in wei of the checked token) subtracted from the minimum amount
+    /// out of every solution, on top of its slippage. It absorbs the rounding of integer-math AMMs,
+    /// whose output can be a few wei below the expected amount, at the cost of a slightly lower
+    /// protection. A non-zero checked amount is used verbatim, without the buffer. Defaults to
+    /// zero, i.e. the minimum amount out is not changed.
+    pub fn with_min_out_buffer(mut self, min_out_buffer: U256) -> Self {
+        self.min_out_buffer = min_out_buffer;
+        self
+    }
+
+    /// Returns the native action to perform for the solution, inferred from its tokens if it
+    /// doesn't set one. See `SplitSwapValidator::infer_native_action`.
+    fn get_native_action(&self, solution: &Solution) -> Option<NativeAction> {
+        self.split_swap_validator
+            .infer_native_action(solution, &self.native_address, &self.wrapped_address)
+    }
+
+    /// Returns the minimum amount out that the router enforces for the solution: its checked
+    /// amount if it's set and non-zero, used verbatim, or its expected amount minus the slippage
+    /// and the min out buffer otherwise, down to zero.
+    pub fn compute_min_out(&self, solution: &Solution) -> U256 {
+        let min_amount_out = biguint_to_u256(&get_min_amount_for_solution(solution.clone()));
+        let has_checked_amount = solution
+            .checked_amount
+            .as_ref()
+            .is_some_and(|checked_amount| *checked_amount != BigUint::ZERO);
+        if has_checked_amount {
+            return min_amount_out;
+        }
+        min_amount_out.saturating_sub(self.min_out_buffer)
+    }
+
+    /// Returns the permit for the solution's given token and the EIP-712 hash that the swapper
+    /// needs to sign for it.
+    ///
+    /// The returned permit must be passed, together with its signature, to
+    /// `encode_strategy_with_permit2_signature`.
+    pub fn permit2_digest(
+        &self,
+        solution: &Solution,
+    ) -> Result<(PermitSingle, [u8; 32]), EncodingError> {
+        let permit2 = self.get_permit2()?;
+        let permit_single = permit2.get_permit_single_for_solution(solution)?;
+        let hash = permit2.get_signing_hash(&permit_single);
+        Ok((permit_single, hash.0))
+    }
+
+    /// Encodes the solution using a permit signed externally. See `permit2_digest`.
+    pub fn encode_strategy_with_permit2_signature(
+        &self,
+        solution: Solution,
+        permit_single: PermitSingle,
+        signature: Vec<u8>,
+    ) -> Result<(Vec<u8>, Bytes), EncodingError> {
+        self.get_permit2()?;
+        let solution = self.validate_solution(solution)?;
+        self.encode_solution(solution, Some((permit_single, signature)))
+    }
+
+    fn get_permit2(&self) -> Result<&Permit2, EncodingError> {
+        self.permit2.as_ref().ok_or_else(|| {
+            EncodingError::FatalError(
+                "Permit2 is not enabled for this split swap strategy encoder".to_string(),
+            )
+        })
+    }
+
+    /// Validates the solution and returns it with the native action to perform set.
+    fn validate_solution(&self, solution: Solution) -> Result<Solution, EncodingError> {
+        // The router and the executors only perform exact input swaps. Encoding an exact out
+        // solution would silently produce exact in calldata.
+        if solution.exact_out {
+            return Err(EncodingError::UnsupportedExactOut);
+        }
+        let mut solution =
+            Solution { native_action: self.get_native_action(&solution), ..solution };
+        if self.deliver_native_as_weth && solution.native_action == Some(NativeAction::Unwrap) {
+            solution.checked_token = self.wrapped_address.clone();
+            solution.native_action = None;
+        }
         self.split_swap_validator
             .validate_solution_min_amounts(&solution)?;
+        self.split_swap_validator
+            .validate_slippage(solution.slippage, self.max_slippage)?;
         self.split_swap_validator
             .validate_split_percentages(&solution.swaps)?;
+        self.split_swap_validator
+            .validate_listed_swap_tokens(&solution.swaps)?;
         self.split_swap_validator
             .validate_swap_path(
                 &solution.swaps,
@@ -120,8 +317,23 @@ impl StrategyEncoder for SplitSwapStrategyEncoder {
                 &self.native_address,
                 &self.wrapped_address,
             )?;
+        Ok(solution)
+    }
 
-        let min_amount_out = get_min_amount_for_solution(solution.clone());
+    /// Encodes a validated solution. If a permit and its signature are given, the router's
+    /// Permit2 swap method is called.
+    fn encode_solution(
+        &self,
+        solution: Solution,
+        permit: Option<(PermitSingle, Vec<u8>)>,
+    ) -> Result<(Vec<u8>, Bytes), EncodingError> {
+        let min_amount_out = self.compute_min_out(&solution);
+        trace_debug!(
+            selector = %self.selector,
+            given_amount = %solution.given_amount,
+            %min_amount_out,
+            "Encoding split swap solution"
+        );
 
         // The tokens array is composed of the given token, the checked token and all the
         // intermediary tokens in between. The contract expects the tokens to be in this order.
@@ -130,7 +342,11 @@ impl StrategyEncoder for SplitSwapStrategyEncoder {
                 .into_iter()
                 .collect();
 
-        let grouped_swaps = group_swaps(solution.swaps);
+        let grouped_swaps = if self.swap_grouping {
+            group_swaps(solution.swaps)
+        } else {
+            ungrouped_swaps(solution.swaps)
+        };
 
         let intermediary_tokens: HashSet<Bytes> = grouped_swaps
             .iter()
@@ -168,6 +384,8 @@ impl StrategyEncoder for SplitSwapStrategyEncoder {
             tokens.push(solution.checked_token.clone());
         }
 
+        trace_debug!(?tokens, "Resolved the tokens of the route");
+
         let mut swaps = vec![];
         for grouped_swap in grouped_swaps.iter() {
             let swap_encoder = self
@@ -193,13 +411,27 @@ impl StrategyEncoder for SplitSwapStrategyEncoder {
                 grouped_protocol_data.extend(protocol_data);
             }
 
+            let token_in_index =
+                get_token_position(tokens.clone(), grouped_swap.input_token.clone())?;
+            let token_out_index =
+                get_token_position(tokens.clone(), grouped_swap.output_token.clone())?;
+            let split = percentage_to_uint24(grouped_swap.split);
+            let executor_address = grouped_swap.executor_address(swap_encoder.as_ref())?;
+            trace_debug!(
+                protocol_system = %grouped_swap.protocol_system,
+                swaps = grouped_swap.swaps.len(),
+                %token_in_index,
+                %token_out_index,
+                split = grouped_swap.split,
+                encoded_split = %split,
+                ?executor_address,
+                "Encoded swap"
+            );
             let swap_data = self.encode_swap_header(
-                get_token_position(tokens.clone(), grouped_swap.input_token.clone())?,
-                get_token_position(tokens.clone(), grouped_swap.output_token.clone())?,
-                percentage_to_uint24(grouped_swap.split),
-                Bytes::from_str(swap_encoder.executor_address()).map_err(|_| {
-                    EncodingError::FatalError("Invalid executor address".to_string())
-                })?,
+                token_in_index,
+                token_out_index,
+                split,
+                executor_address,
                 grouped_protocol_data,
             );
             swaps.push(swap_data);
@@ -211,37 +443,37 @@ impl StrategyEncoder for SplitSwapStrategyEncoder {
         } else {
             tokens.len()
         };
-        let method_calldata = if let Some(permit2) = self.permit2.clone() {
-            let (permit, signature) = permit2.get_permit(
-                &solution.router_address,
-                &solution.sender,
-                &solution.given_token,
-                &solution.given_amount,
-            )?;
+        let given_token = bytes_to_address(&solution.given_token)
+            .map_err(|_| EncodingError::InvalidAddress { field: "given_token" })?;
+        let checked_token = bytes_to_address(&solution.checked_token)
+            .map_err(|_| EncodingError::InvalidAddress { field: "checked_token" })?;
+        let receiver = bytes_to_address(&solution.receiver)
+            .map_err(|_| EncodingError::InvalidAddress { field: "receiver" })?;
+        let method_calldata = if let Some((permit, signature)) = permit {
             (
                 biguint_to_u256(&solution.given_amount),
-                bytes_to_address(&solution.given_token)?,
-                bytes_to_address(&solution.checked_token)?,
-                biguint_to_u256(&min_amount_out),
+                given_token,
+                checked_token,
+                min_amount_out,
                 wrap,
                 unwrap,
                 U256::from(tokens_len),
-                bytes_to_address(&solution.receiver)?,
+                receiver,
                 permit,
-                signature.as_bytes().to_vec(),
+                signature,
                 encoded_swaps,
             )
                 .abi_encode()
         } else {
             (
                 biguint_to_u256(&solution.given_amount),
-                bytes_to_address(&solution.given_token)?,
-                bytes_to_address(&solution.checked_token)?,
-                biguint_to_u256(&min_amount_out),
+                given_token,
+                checked_token,
+                min_amount_out,
                 wrap,
                 unwrap,
                 U256::from(tokens_len),
-                bytes_to_address(&solution.receiver)?,
+                receiver,
                 encoded_swaps,
             )
                 .abi_encode()
@@ -250,12 +482,70 @@ impl StrategyEncoder for SplitSwapStrategyEncoder {
         let contract_interaction = encode_input(&self.selector, method_calldata);
         Ok((contract_interaction, solution.router_address))
     }
+}
+impl EVMStrategyEncoder for SplitSwapStrategyEncoder {}
+
+impl StrategyEncoder for SplitSwapStrategyEncoder {
+    fn encode_strategy(&self, solution: Solution) -> Result<(Vec<u8>, Bytes), EncodingError> {
+        let solution = self.validate_solution(solution)?;
+        let permit = if let Some(permit2) = self.permit2.as_ref() {
+            let (permit, signature) = permit2.get_permit_for_solution(&solution)?;
+            Some((permit, signature.as_bytes().to_vec()))
+        } else {
+            None
+        };
+        self.encode_solution(solution, permit)
+    }
+
+    fn method_signature(&self, _solution: &Solution) -> String {
+        self.selector.clone()
+    }
+
+    fn get_approval_spender(&self, solution: &Solution) -> Result<Option<Bytes>, EncodingError> {
+        // The native token is sent with the transaction, so no approval is needed when wrapping
+        if solution.given_token == self.native_address {
+            return Ok(None);
+        }
+        match &self.permit2 {
+            Some(permit2) => Ok(Some(permit2.address())),
+            None => Ok(Some(solution.router_address.clone())),
+        }
+    }
+
+    fn estimate_gas(&self, solution: &Solution) -> Result<u64, EncodingError> {
+        let token_in_gas = if self.permit2.is_some() { PERMIT2_GAS } else { TRANSFER_FROM_GAS };
+        let native_action_gas = if self
+            .get_native_action(solution)
+            .is_some()
+        {
+            NATIVE_ACTION_GAS
+        } else {
+            0
+        };
+        Ok(BASE_TRANSACTION_GAS +
+            ROUTER_OVERHEAD_GAS +
+            token_in_gas +
+            native_action_gas +
+            self.estimate_swaps_gas(&solution.swaps)?)
+    }
+
+    fn min_amount_out(&self, solution: &Solution) -> Result<Option<BigUint>, EncodingError> {
+        self.split_swap_validator
+            .validate_slippage(solution.slippage, self.max_slippage)?;
+        let min_amount_out = self.compute_min_out(solution);
+        Ok(Some(BigUint::from_bytes_be(&min_amount_out.to_be_bytes::<32>())))
+    }
 
     fn get_swap_encoder(&self, protocol_system: &str) -> Option<&Box<dyn SwapEncoder>> {
         self.swap_encoder_registry
             .get_encoder(protocol_system)
     }
 
+    fn supported_protocols(&self) -> Vec<String> {
+        self.swap_encoder_registry
+            .supported_protocols()
+    }
+
     fn clone_box(&self) -> Box<dyn StrategyEncoder> {
         Box::new(self.clone())
     }
@@ -279,6 +569,7 @@ impl ExecutorStrategyEncoder {
 impl EVMStrategyEncoder for ExecutorStrategyEncoder {}
 impl StrategyEncoder for ExecutorStrategyEncoder {
     fn encode_strategy(&self, solution: Solution) -> Result<(Vec<u8>, Bytes), EncodingError> {
+        SplitSwapValidator.validate_listed_swap_tokens(&solution.swaps)?;
         let grouped_swaps = group_swaps(solution.clone().swaps);
         let number_of_groups = grouped_swaps.len();
         if number_of_groups > 1 {
@@ -317,17 +608,182 @@ impl StrategyEncoder for ExecutorStrategyEncoder {
             grouped_protocol_data.extend(protocol_data);
         }
 
-        let executor_address = Bytes::from_str(swap_encoder.executor_address())
-            .map_err(|_| EncodingError::FatalError("Invalid executor address".to_string()))?;
+        let executor_address = grouped_swap.executor_address(swap_encoder.as_ref())?;
+        trace_debug!(
+            protocol_system = %grouped_swap.protocol_system,
+            swaps = grouped_swap.swaps.len(),
+            ?executor_address,
+            "Encoded direct execution"
+        );
 
         Ok((grouped_protocol_data, executor_address))
     }
 
+    fn method_signature(&self, _solution: &Solution) -> String {
+        DIRECT_EXECUTION_SIGNATURE.to_string()
+    }
+
+    fn get_approval_spender(&self, _solution: &Solution) -> Result<Option<Bytes>, EncodingError> {
+        // The executor is called directly by the holder of the tokens
+        Ok(None)
+    }
+
+    fn estimate_gas(&self, solution: &Solution) -> Result<u64, EncodingError> {
+        Ok(BASE_TRANSACTION_GAS + self.estimate_swaps_gas(&solution.swaps)?)
+    }
+
     fn get_swap_encoder(&self, protocol_system: &str) -> Option<&Box<dyn SwapEncoder>> {
         self.swap_encoder_registry
             .get_encoder(protocol_system)
     }
 
+    fn supported_protocols(&self) -> Vec<String> {
+        self.swap_encoder_registry
+            .supported_protocols()
+    }
+
+    fn clone_box(&self) -> Box<dyn StrategyEncoder> {
+        Box::new(self.clone())
+    }
+}
+
+/// This strategy encoder chooses, for each solution, between direct execution and the router.
+///
+/// A solution is sent directly to the executor using `ExecutorStrategyEncoder` only if the executor
+/// alone can execute it as the router would: it has exactly one swap with a split of 0 (100%), no
+/// checked amount nor slippage (the executor doesn't check the amount out), no native action and
+/// neither its given nor its checked token is the native token (the executor doesn't wrap or
+/// unwrap), and the strategy has no swapper private key (the executor doesn't take Permit2
+/// approvals). Every other solution, including solutions without swaps (which are rejected as
+/// invalid), is encoded for the router using `SplitSwapStrategyEncoder`. The choice only depends on
+/// the solution and the strategy's configuration, so it is always the same for a given solution.
+///
+/// # Fields
+/// * `split_swap_strategy`: SplitSwapStrategyEncoder, used for solutions that need the router
+/// * `executor_strategy`: ExecutorStrategyEncoder, used for single swap solutions
+#[derive(Clone)]
+pub struct AutoStrategyEncoder {
+    split_swap_strategy: SplitSwapStrategyEncoder,
+    executor_strategy: ExecutorStrategyEncoder,
+}
+
+impl AutoStrategyEncoder {
+    pub fn new(
+        blockchain: tycho_core::models::Chain,
+        swap_encoder_registry: SwapEncoderRegistry,
+        swapper_pk: Option<String>,
+        permit2_address: Option<Bytes>,
+    ) -> Result<Self, EncodingError> {
+        Ok(Self {
+            split_swap_strategy: SplitSwapStrategyEncoder::new_with_permit2_address(
+                blockchain,
+                swap_encoder_registry.clone(),
+                swapper_pk,
+                permit2_address,
+            )?,
+            executor_strategy: ExecutorStrategyEncoder::new(swap_encoder_registry),
+        })
+    }
+
+    /// Sets whether the router strategy groups consecutive swaps. See
+    /// `SplitSwapStrategyEncoder::with_swap_grouping`.
+    pub fn with_swap_grouping(mut self, swap_grouping: bool) -> Self {
+        self.split_swap_strategy = self
+            .split_swap_strategy
+            .with_swap_grouping(swap_grouping);
+        self
+    }
+
+    /// Sets the upper bound of the slippage accepted by the router strategy. See
+    /// `SplitSwapStrategyEncoder::with_max_slippage`.
+    pub fn with_max_slippage(mut self, max_slippage: f64) -> Self {
+        self.split_swap_strategy = self
+            .split_swap_strategy
+            .with_max_slippage(max_slippage);
+        self
+    }
+
+    /// Sets whether the router strategy delivers the native token bought by a solution as the
+    /// wrapped token. See `SplitSwapStrategyEncoder::with_deliver_native_as_weth`.
+    pub fn with_deliver_native_as_weth(mut self, deliver_native_as_weth: bool) -> Self {
+        self.split_swap_strategy = self
+            .split_swap_strategy
+            .with_deliver_native_as_weth(deliver_native_as_weth);
+        self
+    }
+
+    /// Sets the amount of the Permit2 allowance of the router strategy. See
+    /// `SplitSwapStrategyEncoder::with_approval_amount`.
+    pub fn with_approval_amount(mut self, approval_amount: ApprovalAmount) -> Self {
+        self.split_swap_strategy = self
+            .split_swap_strategy
+            .with_approval_amount(approval_amount);
+        self
+    }
+
+    /// Sets the amount subtracted from the minimum amount out by the router strategy. See
+    /// `SplitSwapStrategyEncoder::with_min_out_buffer`.
+    pub fn with_min_out_buffer(mut self, min_out_buffer: U256) -> Self {
+        self.split_swap_strategy = self
+            .split_swap_strategy
+            .with_min_out_buffer(min_out_buffer);
+        self
+    }
+
+    /// Returns the strategy encoder to use for the given solution.
+    fn select_strategy(&self, solution: &Solution) -> &dyn StrategyEncoder {
+        let native_address = &self.split_swap_strategy.native_address;
+        let needs_router = solution.checked_amount.is_some() ||
+            solution.slippage.is_some() ||
+            solution.native_action.is_some() ||
+            solution.given_token == *native_address ||
+            solution.checked_token == *native_address ||
+            self.split_swap_strategy
+                .permit2
+                .is_some();
+        match solution.swaps.as_slice() {
+            [swap] if swap.split == 0.0 && !needs_router => &self.executor_strategy,
+            _ => &self.split_swap_strategy,
+        }
+    }
+}
+
+impl StrategyEncoder for AutoStrategyEncoder {
+    fn encode_strategy(&self, solution: Solution) -> Result<(Vec<u8>, Bytes), EncodingError> {
+        self.select_strategy(&solution)
+            .encode_strategy(solution)
+    }
+
+    fn method_signature(&self, solution: &Solution) -> String {
+        self.select_strategy(solution)
+            .method_signature(solution)
+    }
+
+    fn get_approval_spender(&self, solution: &Solution) -> Result<Option<Bytes>, EncodingError> {
+        self.select_strategy(solution)
+            .get_approval_spender(solution)
+    }
+
+    fn estimate_gas(&self, solution: &Solution) -> Result<u64, EncodingError> {
+        self.select_strategy(solution)
+            .estimate_gas(solution)
+    }
+
+    fn min_amount_out(&self, solution: &Solution) -> Result<Option<BigUint>, EncodingError> {
+        self.select_strategy(solution)
+            .min_amount_out(solution)
+    }
+
+    fn get_swap_encoder(&self, protocol_system: &str) -> Option<&Box<dyn SwapEncoder>> {
+        self.split_swap_strategy
+            .get_swap_encoder(protocol_system)
+    }
+
+    fn supported_protocols(&self) -> Vec<String> {
+        self.split_swap_strategy
+            .supported_protocols()
+    }
+
     fn clone_box(&self) -> Box<dyn StrategyEncoder> {
         Box::new(self.clone())
     }
@@ -337,9 +793,13 @@ impl StrategyEncoder for ExecutorStrategyEncoder {
 mod tests {
     use std::{collections::HashMap, str::FromStr};
 
-    use alloy::hex::encode;
-    use alloy_primitives::hex;
+    use alloy::{
+        hex::encode,
+        signers::{local::PrivateKeySigner, SignerSync},
+    };
+    use alloy_primitives::{hex, B256};
     use num_bigint::{BigInt, BigUint};
+    use proptest::prelude::*;
     use rstest::rstest;
     use tycho_core::{
         models::{protocol::ProtocolComponent, Chain as TychoCoreChain},
@@ -383,6 +843,7 @@ mod tests {
             token_in: token_in.clone(),
             token_out: token_out.clone(),
             split: 0f64,
+            executor_address: None,
         };
 
         let solution = Solution {
@@ -399,6 +860,8 @@ mod tests {
             router_address: Bytes::from_str("0x3Ede3eCa2a72B3aeCC820E955B36f38437D01395").unwrap(),
             slippage: None,
             native_action: None,
+            permit2_nonce: None,
+            permit2_deadline: None,
         };
 
         let (protocol_data, executor_address) = encoder
@@ -424,6 +887,90 @@ mod tests {
         );
     }
 
+    #[rstest]
+    #[case::override_address(
+        Some(Bytes::from("0x5615deb798bb3e4dfa0139dfa1b3d433cc23b72f")),
+        Ok(Bytes::from("0x5615deb798bb3e4dfa0139dfa1b3d433cc23b72f"))
+    )]
+    #[case::registry_address(None, Ok(Bytes::from("0xf6c5be66FFf9DC69962d73da0A617a827c382329")))]
+    #[case::invalid_override(
+        Some(Bytes::from("0x5615deb7")),
+        Err(EncodingError::InvalidAddress { field: "executor_address" })
+    )]
+    fn test_executor_strategy_executor_address_override(
+        #[case] executor_address: Option<Bytes>,
+        #[case] expected: Result<Bytes, EncodingError>,
+    ) {
+        let encoder = ExecutorStrategyEncoder::new(get_swap_encoder_registry());
+        let token_out = Bytes::from("0x6b175474e89094c44da98b954eedeac495271d0f");
+        let swap = Swap {
+            component: ProtocolComponent {
+                id: "0xA478c2975Ab1Ea89e8196811F51A7B7Ade33eB11".to_string(),
+                protocol_system: "uniswap_v2".to_string(),
+                ..Default::default()
+            },
+            token_in: weth(),
+            token_out: token_out.clone(),
+            split: 0f64,
+            executor_address,
+        };
+        let solution = Solution {
+            given_token: weth(),
+            given_amount: BigUint::from(1000000000000000000u64),
+            checked_token: token_out,
+            receiver: Bytes::from_str("0x1d96f2f6bef1202e4ce1ff6dad0c2cb002861d3e").unwrap(),
+            swaps: vec![swap],
+            ..Default::default()
+        };
+
+        let result = encoder
+            .encode_strategy(solution)
+            .map(|(_, executor_address)| executor_address);
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_executor_strategy_encode_token_not_in_component() {
+        let swap_encoder_registry = get_swap_encoder_registry();
+        let encoder = ExecutorStrategyEncoder::new(swap_encoder_registry);
+
+        let dai = Bytes::from("0x6b175474e89094c44da98b954eedeac495271d0f");
+        let usdc = Bytes::from("0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48");
+        let swap = Swap {
+            component: ProtocolComponent {
+                id: "0xA478c2975Ab1Ea89e8196811F51A7B7Ade33eB11".to_string(),
+                protocol_system: "uniswap_v2".to_string(),
+                tokens: vec![weth(), dai],
+                ..Default::default()
+            },
+            token_in: weth(),
+            token_out: usdc.clone(),
+            split: 0f64,
+            executor_address: None,
+        };
+        let solution = Solution {
+            given_token: weth(),
+            given_amount: BigUint::from(1000000000000000000u64),
+            checked_token: usdc.clone(),
+            receiver: Bytes::from_str("0x1d96f2f6bef1202e4ce1ff6dad0c2cb002861d3e").unwrap(),
+            swaps: vec![swap],
+            router_address: Bytes::from_str("0x3Ede3eCa2a72B3aeCC820E955B36f38437D01395").unwrap(),
+            ..Default::default()
+        };
+
+        let result = encoder.encode_strategy(solution);
+
+        assert_eq!(
+            result,
+            Err(EncodingError::InvalidInput(format!(
+                "Token {:?} not found in the tokens of component \
+                 0xA478c2975Ab1Ea89e8196811F51A7B7Ade33eB11",
+                usdc
+            )))
+        );
+    }
+
     #[test]
     fn test_executor_strategy_encode_too_many_swaps() {
         let swap_encoder_registry = get_swap_encoder_registry();
@@ -440,6 +987,7 @@ mod tests {
             token_in: token_in.clone(),
             token_out: token_out.clone(),
             split: 0f64,
+            executor_address: None,
         };
 
         let solution = Solution {
@@ -455,6 +1003,8 @@ mod tests {
             router_address: Bytes::from_str("0x3Ede3eCa2a72B3aeCC820E955B36f38437D01395").unwrap(),
             slippage: None,
             native_action: None,
+            permit2_nonce: None,
+            permit2_deadline: None,
         };
 
         let result = encoder.encode_strategy(solution);
@@ -496,6 +1046,7 @@ mod tests {
             token_in: usdc.clone(),
             token_out: eth.clone(),
             split: 0f64,
+            executor_address: None,
         };
 
         let swap_eth_pepe = Swap {
@@ -509,6 +1060,7 @@ mod tests {
             token_in: eth.clone(),
             token_out: pepe.clone(),
             split: 0f64,
+            executor_address: None,
         };
 
         let solution = Solution {
@@ -577,8 +1129,8 @@ mod tests {
     #[case::with_check_and_slippage(
         Some(BigUint::from_str("3_000_000000000000000000").unwrap()),
         Some(0.01f64),
-        Some(BigUint::from_str("2_999_000000000000000000").unwrap()),
-        U256::from_str("2_999_000000000000000000").unwrap(),
+        Some(BigUint::from_str("2_900_000000000000000000").unwrap()),
+        U256::from_str("2_900_000000000000000000").unwrap(),
     )]
     fn test_split_swap_strategy_encoder_simple_route(
         #[case] expected_amount: Option<BigUint>,
@@ -604,6 +1156,7 @@ mod tests {
             token_in: weth.clone(),
             token_out: dai.clone(),
             split: 0f64,
+            executor_address: None,
         };
         let swap_encoder_registry = get_swap_encoder_registry();
         let encoder =
@@ -705,6 +1258,7 @@ mod tests {
             token_in: weth(),
             token_out: dai.clone(),
             split: 0f64,
+            executor_address: None,
         };
         let swap_encoder_registry = get_swap_encoder_registry();
         let encoder =
@@ -754,6 +1308,7 @@ mod tests {
             token_in: dai.clone(),
             token_out: weth(),
             split: 0f64,
+            executor_address: None,
         };
         let swap_encoder_registry = get_swap_encoder_registry();
         let encoder =
@@ -812,6 +1367,7 @@ mod tests {
             token_in: weth.clone(),
             token_out: dai.clone(),
             split: 0.5f64,
+            executor_address: None,
         };
         let swap_weth_wbtc = Swap {
             component: ProtocolComponent {
@@ -824,6 +1380,7 @@ mod tests {
             // This represents the remaining 50%, but to avoid any rounding errors we set this to
             // 0 to signify "the remainder of the WETH value". It should still be very close to 50%
             split: 0f64,
+            executor_address: None,
         };
         let swap_dai_usdc = Swap {
             component: ProtocolComponent {
@@ -834,6 +1391,7 @@ mod tests {
             token_in: dai.clone(),
             token_out: usdc.clone(),
             split: 0f64,
+            executor_address: None,
         };
         let swap_wbtc_usdc = Swap {
             component: ProtocolComponent {
@@ -844,6 +1402,7 @@ mod tests {
             token_in: wbtc.clone(),
             token_out: usdc.clone(),
             split: 0f64,
+            executor_address: None,
         };
         let swap_encoder_registry = get_swap_encoder_registry();
         let encoder =
@@ -912,6 +1471,7 @@ mod tests {
             token_in: usdc.clone(),
             token_out: eth.clone(),
             split: 0f64,
+            executor_address: None,
         };
 
         let swap_eth_pepe = Swap {
@@ -925,6 +1485,7 @@ mod tests {
             token_in: eth.clone(),
             token_out: pepe.clone(),
             split: 0f64,
+            executor_address: None,
         };
         let swap_encoder_registry = get_swap_encoder_registry();
         let encoder =
@@ -1036,6 +1597,7 @@ mod tests {
             token_in: weth.clone(),
             token_out: dai.clone(),
             split: 0f64,
+            executor_address: None,
         };
         let swap_encoder_registry = get_swap_encoder_registry();
         let encoder =
@@ -1125,6 +1687,7 @@ mod tests {
             token_in: eth.clone(),
             token_out: pepe.clone(),
             split: 0f64,
+            executor_address: None,
         };
         let swap_encoder_registry = get_swap_encoder_registry();
         let encoder =
@@ -1188,6 +1751,7 @@ mod tests {
             token_in: usdc.clone(),
             token_out: eth.clone(),
             split: 0f64,
+            executor_address: None,
         };
 
         let swap_encoder_registry = get_swap_encoder_registry();
@@ -1251,6 +1815,7 @@ mod tests {
             token_in: usdc.clone(),
             token_out: weth.clone(),
             split: 0f64,
+            executor_address: None,
         };
 
         // WETH -> USDC (Pool 2)
@@ -1272,6 +1837,7 @@ mod tests {
             token_in: weth.clone(),
             token_out: usdc.clone(),
             split: 0f64,
+            executor_address: None,
         };
 
         let swap_encoder_registry = get_swap_encoder_registry();
@@ -1378,6 +1944,7 @@ mod tests {
             token_in: usdc.clone(),
             token_out: weth.clone(),
             split: 0.6f64, // 60% of input
+            executor_address: None,
         };
 
         // USDC -> WETH (Pool 2) - 40% of input (remaining)
@@ -1399,6 +1966,7 @@ mod tests {
             token_in: usdc.clone(),
             token_out: weth.clone(),
             split: 0f64, // Remaining 40%
+            executor_address: None,
         };
 
         // WETH -> USDC (Pool 2)
@@ -1420,6 +1988,7 @@ mod tests {
             token_in: weth.clone(),
             token_out: usdc.clone(),
             split: 0.0f64,
+            executor_address: None,
         };
 
         let swap_encoder_registry = get_swap_encoder_registry();
@@ -1537,6 +2106,7 @@ mod tests {
             token_in: usdc.clone(),
             token_out: weth.clone(),
             split: 0.0f64,
+            executor_address: None,
         };
 
         let swap_weth_usdc_v3_pool1 = Swap {
@@ -1557,6 +2127,7 @@ mod tests {
             token_in: weth.clone(),
             token_out: usdc.clone(),
             split: 0.6f64,
+            executor_address: None,
         };
 
         let swap_weth_usdc_v3_pool2 = Swap {
@@ -1577,6 +2148,7 @@ mod tests {
             token_in: weth.clone(),
             token_out: usdc.clone(),
             split: 0.0f64,
+            executor_address: None,
         };
 
         let swap_encoder_registry = get_swap_encoder_registry();
@@ -1661,4 +2233,691 @@ mod tests {
         assert_eq!(hex_calldata[..520], expected_input);
         assert_eq!(hex_calldata[1288..], expected_swaps);
     }
+
+    #[test]
+    fn test_split_swap_strategy_encoder_external_permit2_signature() {
+        // Performs a single swap from WETH to DAI on a USV2 pool, signing the permit outside of
+        // the encoder
+        let signer = PrivateKeySigner::from_bytes(
+            &B256::from_str("0x123456789abcdef123456789abcdef123456789abcdef123456789abcdef1234")
+                .unwrap(),
+        )
+        .unwrap();
+        let dai = Bytes::from_str("0x6b175474e89094c44da98b954eedeac495271d0f").unwrap();
+        let swap = Swap {
+            component: ProtocolComponent {
+                id: "0xA478c2975Ab1Ea89e8196811F51A7B7Ade33eB11".to_string(),
+                protocol_system: "uniswap_v2".to_string(),
+                ..Default::default()
+            },
+            token_in: weth(),
+            token_out: dai.clone(),
+            split: 0f64,
+            executor_address: None,
+        };
+        let encoder = SplitSwapStrategyEncoder::new_with_external_signer(
+            eth_chain(),
+            get_swap_encoder_registry(),
+            None,
+        )
+        .unwrap();
+        let solution = Solution {
+            given_token: weth(),
+            given_amount: BigUint::from_str("1_000000000000000000").unwrap(),
+            checked_token: dai,
+            checked_amount: Some(BigUint::from_str("2_640_000000000000000000").unwrap()),
+            sender: Bytes::from_str("0xcd09f75E2BF2A4d11F3AB23f1389FcC1621c0cc2").unwrap(),
+            receiver: Bytes::from_str("0xcd09f75E2BF2A4d11F3AB23f1389FcC1621c0cc2").unwrap(),
+            router_address: Bytes::from_str("0x3Ede3eCa2a72B3aeCC820E955B36f38437D01395").unwrap(),
+            swaps: vec![swap],
+            ..Default::default()
+        };
+
+        // The encoder can't sign the permit itself
+        assert!(encoder
+            .encode_strategy(solution.clone())
+            .is_err());
+
+        let (permit, digest) = encoder
+            .permit2_digest(&solution)
+            .unwrap();
+        let signature = signer
+            .sign_hash_sync(&B256::from(digest))
+            .unwrap()
+            .as_bytes()
+            .to_vec();
+        let (calldata, target) = encoder
+            .encode_strategy_with_permit2_signature(solution, permit, signature.clone())
+            .unwrap();
+        let hex_calldata = encode(&calldata);
+
+        assert_eq!(target, Bytes::from_str("0x3Ede3eCa2a72B3aeCC820E955B36f38437D01395").unwrap());
+        assert_eq!(&hex_calldata[..8], "d499aa88");
+        assert!(hex_calldata.contains(&encode(&signature)));
+    }
+
+    #[test]
+    fn test_split_swap_strategy_approval_spender() {
+        let router_address = Bytes::from_str("0x3Ede3eCa2a72B3aeCC820E955B36f38437D01395").unwrap();
+        let encoder =
+            SplitSwapStrategyEncoder::new(eth_chain(), get_swap_encoder_registry(), None).unwrap();
+        let solution = Solution {
+            given_token: weth(),
+            router_address: router_address.clone(),
+            ..Default::default()
+        };
+        let native_solution = Solution { given_token: eth(), ..solution.clone() };
+
+        assert_eq!(encoder.get_approval_spender(&solution), Ok(Some(router_address)));
+        assert_eq!(encoder.get_approval_spender(&native_solution), Ok(None));
+    }
+
+    #[rstest]
+    #[case::grouping_enabled(true, 2)]
+    #[case::grouping_disabled(false, 3)]
+    fn test_split_swap_strategy_swap_grouping(
+        #[case] swap_grouping: bool,
+        #[case] expected_tokens_len: u8,
+    ) {
+        //   USDC ──(USV4)──> ETH ───(USV4)──> PEPE
+        //
+        // When grouped, ETH is not an intermediary token of the router since both swaps are
+        // executed in the same call.
+        let eth = eth();
+        let usdc = Bytes::from_str("0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48").unwrap();
+        let pepe = Bytes::from_str("0x6982508145454Ce325dDbE47a25d4ec3d2311933").unwrap();
+        let mut static_attributes: HashMap<String, Bytes> = HashMap::new();
+        static_attributes
+            .insert("key_lp_fee".into(), Bytes::from(BigInt::from(3000).to_signed_bytes_be()));
+        static_attributes
+            .insert("tick_spacing".into(), Bytes::from(BigInt::from(60).to_signed_bytes_be()));
+        let component = ProtocolComponent {
+            id: "0xdce6394339af00981949f5f3baf27e3610c76326a700af57e4b3e3ae4977f78d".to_string(),
+            protocol_system: "uniswap_v4".to_string(),
+            static_attributes,
+            ..Default::default()
+        };
+        let swap_usdc_eth = Swap {
+            component: component.clone(),
+            token_in: usdc.clone(),
+            token_out: eth.clone(),
+            split: 0f64,
+            executor_address: None,
+        };
+        let swap_eth_pepe = Swap {
+            component,
+            token_in: eth.clone(),
+            token_out: pepe.clone(),
+            split: 0f64,
+            executor_address: None,
+        };
+        let encoder = SplitSwapStrategyEncoder::new(eth_chain(), get_swap_encoder_registry(), None)
+            .unwrap()
+            .with_swap_grouping(swap_grouping);
+        let solution = Solution {
+            given_token: usdc,
+            given_amount: BigUint::from_str("1000_000000").unwrap(),
+            checked_token: pepe,
+            checked_amount: Some(BigUint::from_str("97191013220606467325121599").unwrap()),
+            sender: Bytes::from_str("0xcd09f75E2BF2A4d11F3AB23f1389FcC1621c0cc2").unwrap(),
+            receiver: Bytes::from_str("0xcd09f75E2BF2A4d11F3AB23f1389FcC1621c0cc2").unwrap(),
+            router_address: Bytes::from_str("0x3Ede3eCa2a72B3aeCC820E955B36f38437D01395").unwrap(),
+            swaps: vec![swap_usdc_eth, swap_eth_pepe],
+            ..Default::default()
+        };
+
+        let (calldata, _) = encoder
+            .encode_strategy(solution)
+            .unwrap();
+
+        // The tokens length is the 7th argument, after the 4 bytes of the selector
+        let tokens_len = &calldata[4 + 32 * 6..4 + 32 * 7];
+        assert_eq!(U256::from_be_slice(tokens_len), U256::from(expected_tokens_len));
+    }
+
+    #[rstest]
+    #[case::slippage(None, Some(0.01), 990)]
+    #[case::checked_amount(Some(995), None, 995)]
+    #[case::checked_amount_above_slippage(Some(995), Some(0.01), 995)]
+    #[case::checked_amount_below_slippage(Some(980), Some(0.01), 980)]
+    #[case::zero_checked_amount(Some(0), Some(0.01), 990)]
+    fn test_split_swap_strategy_compute_min_out(
+        #[case] checked_amount: Option<u64>,
+        #[case] slippage: Option<f64>,
+        #[case] expected_min_out: u64,
+    ) {
+        let encoder =
+            SplitSwapStrategyEncoder::new(eth_chain(), get_swap_encoder_registry(), None).unwrap();
+        let solution = Solution {
+            expected_amount: Some(BigUint::from(1000u64)),
+            checked_amount: checked_amount.map(BigUint::from),
+            slippage,
+            ..Default::default()
+        };
+
+        assert_eq!(encoder.compute_min_out(&solution), U256::from(expected_min_out));
+    }
+
+    #[rstest]
+    #[case::no_buffer(0, 990)]
+    #[case::buffer(2, 988)]
+    #[case::buffer_above_min_out(1000, 0)]
+    fn test_split_swap_strategy_compute_min_out_with_buffer(
+        #[case] min_out_buffer: u64,
+        #[case] expected_min_out: u64,
+    ) {
+        let encoder = SplitSwapStrategyEncoder::new(eth_chain(), get_swap_encoder_registry(), None)
+            .unwrap()
+            .with_min_out_buffer(U256::from(min_out_buffer));
+        let solution = Solution {
+            expected_amount: Some(BigUint::from(1000u64)),
+            slippage: Some(0.01),
+            ..Default::default()
+        };
+
+        assert_eq!(encoder.compute_min_out(&solution), U256::from(expected_min_out));
+    }
+
+    #[test]
+    fn test_split_swap_strategy_compute_min_out_ignores_buffer_for_checked_amount() {
+        let encoder = SplitSwapStrategyEncoder::new(eth_chain(), get_swap_encoder_registry(), None)
+            .unwrap()
+            .with_min_out_buffer(U256::from(2u64));
+        let solution = Solution {
+            expected_amount: Some(BigUint::from(1000u64)),
+            checked_amount: Some(BigUint::from(995u64)),
+            slippage: Some(0.01),
+            ..Default::default()
+        };
+
+        assert_eq!(encoder.compute_min_out(&solution), U256::from(995u64));
+    }
+
+    #[test]
+    fn test_split_swap_strategy_infers_native_action() {
+        let dai = Bytes::from_str("0x6b175474e89094c44da98b954eedeac495271d0f").unwrap();
+        let swap_weth_dai = Swap {
+            component: ProtocolComponent {
+                id: "0xA478c2975Ab1Ea89e8196811F51A7B7Ade33eB11".to_string(),
+                protocol_system: "uniswap_v2".to_string(),
+                ..Default::default()
+            },
+            token_in: weth(),
+            token_out: dai.clone(),
+            split: 0f64,
+            executor_address: None,
+        };
+        let swap_dai_weth =
+            Swap { token_in: dai.clone(), token_out: weth(), ..swap_weth_dai.clone() };
+        let encoder =
+            SplitSwapStrategyEncoder::new(eth_chain(), get_swap_encoder_registry(), None).unwrap();
+
+        let wrap_solution = Solution {
+            given_token: eth(),
+            checked_token: dai.clone(),
+            swaps: vec![swap_weth_dai.clone()],
+            ..Default::default()
+        };
+        let unwrap_solution = Solution {
+            given_token: dai.clone(),
+            checked_token: eth(),
+            swaps: vec![swap_dai_weth],
+            ..Default::default()
+        };
+        let explicit_solution = Solution {
+            given_token: eth(),
+            checked_token: dai.clone(),
+            swaps: vec![swap_weth_dai.clone()],
+            native_action: Some(NativeAction::Unwrap),
+            ..Default::default()
+        };
+        let no_action_solution = Solution {
+            given_token: weth(),
+            checked_token: dai,
+            swaps: vec![swap_weth_dai],
+            ..Default::default()
+        };
+
+        assert_eq!(encoder.get_native_action(&wrap_solution), Some(NativeAction::Wrap));
+        assert_eq!(encoder.get_native_action(&unwrap_solution), Some(NativeAction::Unwrap));
+        assert_eq!(encoder.get_native_action(&explicit_solution), Some(NativeAction::Unwrap));
+        assert_eq!(encoder.get_native_action(&no_action_solution), None);
+    }
+
+    #[test]
+    fn test_split_swap_strategy_reconciles_checked_token() {
+        // The last swap buys WETH while ETH is checked: it's reconciled by unwrapping, and the
+        // checked amount is enforced on the unwrapped ETH. The reverse can't be reconciled, since
+        // the router only wraps before the swaps.
+        let dai = Bytes::from_str("0x6b175474e89094c44da98b954eedeac495271d0f").unwrap();
+        let swap_dai_weth = Swap {
+            component: ProtocolComponent {
+                id: "0xA478c2975Ab1Ea89e8196811F51A7B7Ade33eB11".to_string(),
+                protocol_system: "uniswap_v2".to_string(),
+                ..Default::default()
+            },
+            token_in: dai.clone(),
+            token_out: weth(),
+            split: 0f64,
+            executor_address: None,
+        };
+        let swap_dai_eth = Swap {
+            component: ProtocolComponent {
+                protocol_system: "uniswap_v4".to_string(),
+                ..swap_dai_weth.component.clone()
+            },
+            token_out: eth(),
+            ..swap_dai_weth.clone()
+        };
+        let encoder =
+            SplitSwapStrategyEncoder::new(eth_chain(), get_swap_encoder_registry(), None).unwrap();
+        let solution = Solution {
+            given_token: dai,
+            checked_token: eth(),
+            checked_amount: Some(BigUint::from(1000u64)),
+            swaps: vec![swap_dai_weth],
+            ..Default::default()
+        };
+        let wrapped_solution =
+            Solution { checked_token: weth(), swaps: vec![swap_dai_eth], ..solution.clone() };
+
+        let solution = encoder
+            .validate_solution(solution)
+            .unwrap();
+        let result = encoder.validate_solution(wrapped_solution);
+
+        assert_eq!(solution.native_action, Some(NativeAction::Unwrap));
+        assert_eq!(solution.checked_token, eth());
+        assert_eq!(encoder.compute_min_out(&solution), U256::from(1000u64));
+        assert!(matches!(
+            result,
+            Err(EncodingError::InvalidInput(msg)) if msg.contains("can't wrap")
+        ));
+    }
+
+    #[test]
+    fn test_split_swap_strategy_deliver_native_as_weth() {
+        let dai = Bytes::from_str("0x6b175474e89094c44da98b954eedeac495271d0f").unwrap();
+        let swap_dai_weth = Swap {
+            component: ProtocolComponent {
+                id: "0xA478c2975Ab1Ea89e8196811F51A7B7Ade33eB11".to_string(),
+                protocol_system: "uniswap_v2".to_string(),
+                ..Default::default()
+            },
+            token_in: dai.clone(),
+            token_out: weth(),
+            split: 0f64,
+            executor_address: None,
+        };
+        let encoder = SplitSwapStrategyEncoder::new(eth_chain(), get_swap_encoder_registry(), None)
+            .unwrap()
+            .with_deliver_native_as_weth(true);
+        let solution = Solution {
+            given_token: dai,
+            checked_token: eth(),
+            checked_amount: Some(BigUint::from(1000u64)),
+            swaps: vec![swap_dai_weth],
+            ..Default::default()
+        };
+
+        let solution = encoder
+            .validate_solution(solution)
+            .unwrap();
+
+        assert_eq!(solution.checked_token, weth());
+        assert_eq!(solution.native_action, None);
+        assert_eq!(encoder.compute_min_out(&solution), U256::from(1000u64));
+    }
+
+    #[test]
+    fn test_split_swap_strategy_encoder_unsupported_chain() {
+        let result = SplitSwapStrategyEncoder::new(
+            TychoCoreChain::Starknet,
+            get_swap_encoder_registry(),
+            None,
+        );
+
+        assert!(matches!(result, Err(EncodingError::InvalidInput(_))));
+    }
+
+    #[test]
+    fn test_split_swap_strategy_encoder_exact_out_fails() {
+        let dai = Bytes::from_str("0x6b175474e89094c44da98b954eedeac495271d0f").unwrap();
+        let swap = Swap {
+            component: ProtocolComponent {
+                id: "0xA478c2975Ab1Ea89e8196811F51A7B7Ade33eB11".to_string(),
+                protocol_system: "uniswap_v2".to_string(),
+                ..Default::default()
+            },
+            token_in: weth(),
+            token_out: dai.clone(),
+            split: 0f64,
+            executor_address: None,
+        };
+        let encoder =
+            SplitSwapStrategyEncoder::new(eth_chain(), get_swap_encoder_registry(), None).unwrap();
+        let solution = Solution {
+            exact_out: true,
+            given_token: weth(),
+            given_amount: BigUint::from_str("1_000000000000000000").unwrap(),
+            checked_token: dai,
+            checked_amount: Some(BigUint::from_str("2_640_000000000000000000").unwrap()),
+            sender: Bytes::from_str("0xcd09f75E2BF2A4d11F3AB23f1389FcC1621c0cc2").unwrap(),
+            receiver: Bytes::from_str("0xcd09f75E2BF2A4d11F3AB23f1389FcC1621c0cc2").unwrap(),
+            router_address: Bytes::from_str("0x3Ede3eCa2a72B3aeCC820E955B36f38437D01395").unwrap(),
+            swaps: vec![swap],
+            ..Default::default()
+        };
+
+        let result = encoder.encode_strategy(solution);
+
+        assert_eq!(result, Err(EncodingError::UnsupportedExactOut));
+    }
+
+    #[test]
+    fn test_split_swap_strategy_estimate_gas() {
+        // Estimates the gas of a sequential swap from WETH to USDC through DAI using USV2 pools,
+        // without permit2
+        let dai = Bytes::from_str("0x6b175474e89094c44da98b954eedeac495271d0f").unwrap();
+        let usdc = Bytes::from_str("0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48").unwrap();
+
+        let swap_weth_dai = Swap {
+            component: ProtocolComponent {
+                id: "0xA478c2975Ab1Ea89e8196811F51A7B7Ade33eB11".to_string(),
+                protocol_system: "uniswap_v2".to_string(),
+                ..Default::default()
+            },
+            token_in: weth(),
+            token_out: dai.clone(),
+            split: 0f64,
+            executor_address: None,
+        };
+        let swap_dai_usdc = Swap {
+            component: ProtocolComponent {
+                id: "0xAE461cA67B15dc8dc81CE7615e0320dA1A9aB8D5".to_string(),
+                protocol_system: "uniswap_v2".to_string(),
+                ..Default::default()
+            },
+            token_in: dai,
+            token_out: usdc.clone(),
+            split: 0f64,
+            executor_address: None,
+        };
+        let swap_encoder_registry = get_swap_encoder_registry();
+        let encoder =
+            SplitSwapStrategyEncoder::new(eth_chain(), swap_encoder_registry, None).unwrap();
+        let solution = Solution {
+            given_token: weth(),
+            checked_token: usdc,
+            swaps: vec![swap_weth_dai, swap_dai_usdc],
+            ..Default::default()
+        };
+
+        let gas = encoder.estimate_gas(&solution).unwrap();
+        let usv2_gas = encoder
+            .get_swap_encoder("uniswap_v2")
+            .unwrap()
+            .gas_estimate();
+        assert_eq!(
+            gas,
+            BASE_TRANSACTION_GAS + ROUTER_OVERHEAD_GAS + TRANSFER_FROM_GAS + 2 * usv2_gas
+        );
+    }
+
+    #[test]
+    fn test_executor_strategy_estimate_gas_unknown_protocol() {
+        let swap_encoder_registry = get_swap_encoder_registry();
+        let encoder = ExecutorStrategyEncoder::new(swap_encoder_registry);
+
+        let swap = Swap {
+            component: ProtocolComponent {
+                protocol_system: "not_a_protocol".to_string(),
+                ..Default::default()
+            },
+            token_in: weth(),
+            token_out: Bytes::from("0x6b175474e89094c44da98b954eedeac495271d0f"),
+            split: 0f64,
+            executor_address: None,
+        };
+        let solution = Solution { swaps: vec![swap], ..Default::default() };
+
+        let result = encoder.estimate_gas(&solution);
+        assert_eq!(
+            result,
+            Err(EncodingError::InvalidInput(
+                "Swap encoder not found for protocol: not_a_protocol".to_string()
+            ))
+        );
+    }
+
+    fn auto_strategy_swap(token_in: Bytes, token_out: Bytes) -> Swap {
+        Swap {
+            component: ProtocolComponent {
+                id: "0xA478c2975Ab1Ea89e8196811F51A7B7Ade33eB11".to_string(),
+                protocol_system: "uniswap_v2".to_string(),
+                ..Default::default()
+            },
+            token_in,
+            token_out,
+            split: 0f64,
+            executor_address: None,
+        }
+    }
+
+    fn auto_strategy_solution() -> Solution {
+        let dai = Bytes::from_str("0x6b175474e89094c44da98b954eedeac495271d0f").unwrap();
+        Solution {
+            given_token: weth(),
+            given_amount: BigUint::from_str("1_000000000000000000").unwrap(),
+            checked_token: dai.clone(),
+            sender: Bytes::from_str("0xcd09f75E2BF2A4d11F3AB23f1389FcC1621c0cc2").unwrap(),
+            receiver: Bytes::from_str("0xcd09f75E2BF2A4d11F3AB23f1389FcC1621c0cc2").unwrap(),
+            router_address: Bytes::from_str("0x3Ede3eCa2a72B3aeCC820E955B36f38437D01395").unwrap(),
+            swaps: vec![auto_strategy_swap(weth(), dai)],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_auto_strategy_selects_strategy() {
+        let dai = Bytes::from_str("0x6b175474e89094c44da98b954eedeac495271d0f").unwrap();
+        let usdc = Bytes::from_str("0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48").unwrap();
+        let encoder =
+            AutoStrategyEncoder::new(eth_chain(), get_swap_encoder_registry(), None, None).unwrap();
+        let single_swap_solution = auto_strategy_solution();
+        let mut sequential_solution = Solution {
+            checked_token: usdc.clone(),
+            checked_amount: Some(BigUint::from_str("2_640_000000").unwrap()),
+            ..auto_strategy_solution()
+        };
+        sequential_solution
+            .swaps
+            .push(auto_strategy_swap(dai, usdc));
+
+        let (_, single_swap_target) = encoder
+            .encode_strategy(single_swap_solution.clone())
+            .unwrap();
+        let (_, sequential_target) = encoder
+            .encode_strategy(sequential_solution.clone())
+            .unwrap();
+
+        // The single swap is sent directly to the USV2 executor
+        assert_eq!(
+            single_swap_target,
+            Bytes::from_str("0xf6c5be66FFf9DC69962d73da0A617a827c382329").unwrap()
+        );
+        assert_eq!(
+            encoder.method_signature(&single_swap_solution),
+            DIRECT_EXECUTION_SIGNATURE.to_string()
+        );
+        assert_eq!(sequential_target, sequential_solution.router_address);
+    }
+
+    #[rstest]
+    #[case::checked_amount(Solution {
+        checked_amount: Some(BigUint::from(1u64)),
+        ..auto_strategy_solution()
+    })]
+    #[case::slippage(Solution {
+        expected_amount: Some(BigUint::from(1000u64)),
+        slippage: Some(0.01),
+        ..auto_strategy_solution()
+    })]
+    #[case::native_action(Solution {
+        native_action: Some(NativeAction::Wrap),
+        ..auto_strategy_solution()
+    })]
+    #[case::native_given_token(Solution { given_token: eth(), ..auto_strategy_solution() })]
+    #[case::native_checked_token(Solution { checked_token: eth(), ..auto_strategy_solution() })]
+    fn test_auto_strategy_single_swap_needs_router(#[case] solution: Solution) {
+        let encoder =
+            AutoStrategyEncoder::new(eth_chain(), get_swap_encoder_registry(), None, None).unwrap();
+
+        // The executor alone wouldn't check the amount out nor wrap or unwrap the native token
+        assert_ne!(encoder.method_signature(&solution), DIRECT_EXECUTION_SIGNATURE.to_string());
+    }
+
+    #[test]
+    fn test_auto_strategy_single_swap_with_permit2_needs_router() {
+        let private_key =
+            "0x123456789abcdef123456789abcdef123456789abcdef123456789abcdef1234".to_string();
+        let encoder = AutoStrategyEncoder::new(
+            eth_chain(),
+            get_swap_encoder_registry(),
+            Some(private_key),
+            None,
+        )
+        .unwrap();
+        let solution = auto_strategy_solution();
+
+        // The executor can't take the Permit2 approval of the swapper
+        assert_ne!(encoder.method_signature(&solution), DIRECT_EXECUTION_SIGNATURE.to_string());
+    }
+
+    /// Computes the amount sold by each swap the way `TychoRouter._swap` does for swaps that all
+    /// sell the given token: `amount * split / 0xffffff` for a non-zero split, and the remaining
+    /// amount for a zero split. Returns `None` if the remaining amount would underflow.
+    fn router_leg_amounts(given_amount: U256, splits: &[u32]) -> Option<Vec<U256>> {
+        let mut remaining = given_amount;
+        let mut amounts = vec![];
+        for &split in splits {
+            let amount = if split > 0 {
+                given_amount * U256::from(split) / U256::from(0xffffffu32)
+            } else {
+                remaining
+            };
+            remaining = remaining.checked_sub(amount)?;
+            amounts.push(amount);
+        }
+        Some(amounts)
+    }
+
+    /// Reads the raw uint24 splits of the swaps from the calldata of a `swap` router call.
+    fn raw_uint24_splits(calldata: &[u8]) -> Vec<u32> {
+        let (.., encoded_swaps) = <(
+            U256,
+            alloy_primitives::Address,
+            alloy_primitives::Address,
+            U256,
+            bool,
+            bool,
+            U256,
+            alloy_primitives::Address,
+            alloy_primitives::Bytes,
+        )>::abi_decode_params(&calldata[4..], true)
+        .unwrap();
+        let mut encoded_swaps: &[u8] = &encoded_swaps;
+        let mut splits = vec![];
+        while !encoded_swaps.is_empty() {
+            let length = u16::from_be_bytes([encoded_swaps[0], encoded_swaps[1]]) as usize;
+            // Each swap starts with the token in and token out indices, followed by the split
+            let swap = &encoded_swaps[2..2 + length];
+            splits.push(u32::from_be_bytes([0, swap[2], swap[3], swap[4]]));
+            encoded_swaps = &encoded_swaps[2 + length..];
+        }
+        splits
+    }
+
+    proptest! {
+        /// The router splits the given amount between the swaps as requested: each swap with a
+        /// split sells its share of the given amount, up to the rounding of the split to a uint24,
+        /// no swap sells nothing, and the amounts sold by the swaps sum to the given amount. The
+        /// weights give each swap a share of at least 1/8000, and the given amount is large
+        /// enough for such a share to be at least 1 wei.
+        #[test]
+        fn test_split_swap_leg_amounts_match_splits(
+            weights in prop::collection::vec(1u32..1000, 1..8),
+            remainder_weight in 1u32..1000,
+            given_amount in 1_000_000u128..,
+        ) {
+            let weth = weth();
+            let dai = Bytes::from_str("0x6b175474e89094c44da98b954eedeac495271d0f").unwrap();
+            let total_weight: u32 = weights.iter().sum::<u32>() + remainder_weight;
+            let splits = weights
+                .iter()
+                .map(|weight| *weight as f64 / total_weight as f64)
+                .chain([0f64]);
+            let swaps = splits
+                .enumerate()
+                .map(|(i, split)| Swap {
+                    component: ProtocolComponent {
+                        id: format!("0x{:040x}", i + 1),
+                        protocol_system: "uniswap_v2".to_string(),
+                        ..Default::default()
+                    },
+                    token_in: weth.clone(),
+                    token_out: dai.clone(),
+                    split,
+                    executor_address: None,
+                })
+                .collect();
+            let encoder =
+                SplitSwapStrategyEncoder::new(eth_chain(), get_swap_encoder_registry(), None)
+                    .unwrap();
+            let solution = Solution {
+                given_token: weth.clone(),
+                given_amount: BigUint::from(given_amount),
+                checked_token: dai.clone(),
+                checked_amount: Some(BigUint::from(1u32)),
+                sender: Bytes::from_str("0xcd09f75E2BF2A4d11F3AB23f1389FcC1621c0cc2").unwrap(),
+                receiver: Bytes::from_str("0xcd09f75E2BF2A4d11F3AB23f1389FcC1621c0cc2").unwrap(),
+                router_address: Bytes::from_str("0x3Ede3eCa2a72B3aeCC820E955B36f38437D01395")
+                    .unwrap(),
+                swaps,
+                ..Default::default()
+            };
+
+            let (calldata, _) = encoder.encode_strategy(solution).unwrap();
+            let encoded_splits = raw_uint24_splits(&calldata);
+            prop_assert_eq!(encoded_splits.len(), weights.len() + 1);
+            prop_assert_eq!(encoded_splits.last(), Some(&0));
+
+            let given_amount = U256::from(given_amount);
+            let amounts = router_leg_amounts(given_amount, &encoded_splits);
+            prop_assert!(amounts.is_some(), "The splits exceed the given amount");
+            let amounts = amounts.unwrap();
+            // Rounding the split to a uint24 and the amount down moves it by less than a step
+            let tolerance = given_amount / U256::from(0xffffffu32) + U256::from(1u32);
+            for (amount, weight) in amounts.iter().zip(&weights) {
+                let expected =
+                    given_amount * U256::from(*weight) / U256::from(total_weight);
+                let difference = if *amount > expected {
+                    *amount - expected
+                } else {
+                    expected - *amount
+                };
+                prop_assert!(
+                    difference <= tolerance,
+                    "Swap sells {} instead of {}",
+                    amount,
+                    expected
+                );
+            }
+            prop_assert!(amounts.iter().all(|amount| *amount > U256::ZERO));
+            let total = amounts
+                .into_iter()
+                .fold(U256::ZERO, |total, amount| total + amount);
+            prop_assert_eq!(total, given_amount);
+        }
+    }
 }
@@ -40,8 +40,9 @@ impl SplitSwapValidator {
             if token_swaps.len() == 1 {
                 if token_swaps[0].split != 0.0 {
                     return Err(EncodingError::InvalidInput(format!(
-                        "Single swap must have 0% split for token {:?}",
-                        token
+                        "Single swap must have 0% split for token {:?}, got {}%",
+                        token,
+                        token_swaps[0].split * 100.0
                     )));
                 }
                 continue;
@@ -72,21 +73,47 @@ impl SplitSwapValidator {
 
             if !found_zero_split {
                 return Err(EncodingError::InvalidInput(format!(
-                    "Token {:?} must have exactly one 0% split for remainder handling",
-                    token
+                    "Token {:?} must have exactly one 0% split for remainder handling, splits sum \
+                     to {}%",
+                    token,
+                    total_percentage * 100.0
                 )));
             }
 
             // Total must be <100% to leave room for remainder
             if total_percentage >= 1.0 {
-                return Err(EncodingError::InvalidInput(format!(
-                    "Total of non-remainder splits for token {:?} must be <100%, got {}%",
-                    token,
-                    total_percentage * 100.0
-                )));
+                return Err(EncodingError::InvalidSplit { token, sum: total_percentage });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Raises an error if the input or output token of a swap is not one of the tokens of the
+    /// swap's component.
+    pub fn validate_swap_tokens(&self, swaps: &[Swap]) -> Result<(), EncodingError> {
+        for swap in swaps {
+            for token in [&swap.token_in, &swap.token_out] {
+                if !swap.component.tokens.contains(token) {
+                    return Err(EncodingError::InvalidInput(format!(
+                        "Token {:?} not found in the tokens of component {}",
+                        token, swap.component.id
+                    )));
+                }
             }
         }
+        Ok(())
+    }
 
+    /// Like `validate_swap_tokens`, but skips the swaps whose component doesn't list its tokens, so
+    /// it can be checked on every solution before encoding it.
+    pub fn validate_listed_swap_tokens(&self, swaps: &[Swap]) -> Result<(), EncodingError> {
+        for swap in swaps
+            .iter()
+            .filter(|swap| !swap.component.tokens.is_empty())
+        {
+            self.validate_swap_tokens(std::slice::from_ref(swap))?;
+        }
         Ok(())
     }
 
@@ -103,6 +130,54 @@ impl SplitSwapValidator {
         Ok(())
     }
 
+    /// Raises an error if the slippage is set and is not within `[0, max_slippage]`. A
+    /// `max_slippage` above 1 (100%) is capped, since the minimum amount out can't be negative.
+    pub fn validate_slippage(
+        &self,
+        slippage: Option<f64>,
+        max_slippage: f64,
+    ) -> Result<(), EncodingError> {
+        let max_slippage = max_slippage.min(1.0);
+        match slippage {
+            Some(slippage) if !(0.0..=max_slippage).contains(&slippage) => {
+                Err(EncodingError::InvalidInput(format!(
+                    "Slippage must be between 0 and {}, got {}",
+                    max_slippage, slippage
+                )))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns the native action to perform for the solution.
+    ///
+    /// If the solution doesn't set one explicitly, a wrap is inferred when the given token is the
+    /// chain's native token and the first swap sells the wrapped token. Likewise, an unwrap is
+    /// inferred when the checked token is the native token and the last swap buys the wrapped
+    /// token, so the checked amount is enforced on the native token after unwrapping. Swaps that
+    /// trade the native token directly (e.g. on Uniswap V4) need no action.
+    pub fn infer_native_action(
+        &self,
+        solution: &Solution,
+        native_address: &Bytes,
+        wrapped_address: &Bytes,
+    ) -> Option<NativeAction> {
+        if solution.native_action.is_some() {
+            return solution.native_action.clone();
+        }
+        let first_swap = solution.swaps.first()?;
+        let last_swap = solution.swaps.last()?;
+        if solution.given_token == *native_address && first_swap.token_in == *wrapped_address {
+            Some(NativeAction::Wrap)
+        } else if solution.checked_token == *native_address &&
+            last_swap.token_out == *wrapped_address
+        {
+            Some(NativeAction::Unwrap)
+        } else {
+            None
+        }
+    }
+
     /// Raises an error if swaps do not represent a valid path from the given token to the checked
     /// token.
     ///
@@ -186,6 +261,23 @@ impl SplitSwapValidator {
 
         // If we get here, either checked_token wasn't reached or not all tokens were visited
         if !visited.contains(checked_token) {
+            // Point out the wrapping mismatches, which the router can't always reconcile
+            if checked_token == wrapped_address && visited.contains(native_address) {
+                return Err(EncodingError::InvalidInput(
+                    "Checked token is not reachable through swap path: the swaps buy the native \
+                     token, but the router can't wrap it into the checked wrapped token after the \
+                     swaps"
+                        .to_string(),
+                ));
+            }
+            if checked_token == native_address && visited.contains(wrapped_address) {
+                return Err(EncodingError::InvalidInput(
+                    "Checked token is not reachable through swap path: the swaps buy the wrapped \
+                     token, which is only unwrapped into the checked native token with an unwrap \
+                     native action"
+                        .to_string(),
+                ));
+            }
             Err(EncodingError::InvalidInput(
                 "Checked token is not reachable through swap path".to_string(),
             ))
@@ -223,6 +315,7 @@ mod tests {
             token_in: weth.clone(),
             token_out: dai.clone(),
             split: 0f64,
+            executor_address: None,
         }];
         let result = validator.validate_swap_path(&swaps, &weth, &dai, &None, &eth, &weth);
         assert_eq!(result, Ok(()));
@@ -245,6 +338,7 @@ mod tests {
                 token_in: weth.clone(),
                 token_out: dai.clone(),
                 split: 0.5f64,
+                executor_address: None,
             },
             Swap {
                 component: ProtocolComponent {
@@ -255,6 +349,7 @@ mod tests {
                 token_in: dai.clone(),
                 token_out: usdc.clone(),
                 split: 0f64,
+                executor_address: None,
             },
         ];
         let result = validator.validate_swap_path(&swaps, &weth, &usdc, &None, &eth, &weth);
@@ -280,6 +375,7 @@ mod tests {
                 token_in: weth.clone(),
                 token_out: dai.clone(),
                 split: 0.5,
+                executor_address: None,
             },
             // This swap is disconnected from the WETH->DAI path
             Swap {
@@ -291,6 +387,7 @@ mod tests {
                 token_in: wbtc.clone(),
                 token_out: usdc.clone(),
                 split: 0.0,
+                executor_address: None,
             },
         ];
         let result =
@@ -318,6 +415,7 @@ mod tests {
                 token_in: usdc.clone(),
                 token_out: weth.clone(),
                 split: 0f64,
+                executor_address: None,
             },
             Swap {
                 component: ProtocolComponent {
@@ -328,6 +426,7 @@ mod tests {
                 token_in: weth.clone(),
                 token_out: usdc.clone(),
                 split: 0f64,
+                executor_address: None,
             },
         ];
 
@@ -353,6 +452,7 @@ mod tests {
             token_in: weth.clone(),
             token_out: dai.clone(),
             split: 1.0,
+            executor_address: None,
         }];
         let result =
             validator.validate_swap_path(&unreachable_swaps, &weth, &usdc, &None, &eth, &weth);
@@ -391,6 +491,7 @@ mod tests {
             token_in: weth.clone(),
             token_out: dai.clone(),
             split: 0f64,
+            executor_address: None,
         }];
         let result = validator.validate_split_percentages(&swaps);
         assert_eq!(result, Ok(()));
@@ -413,6 +514,7 @@ mod tests {
                 token_in: weth.clone(),
                 token_out: dai.clone(),
                 split: 0.5,
+                executor_address: None,
             },
             Swap {
                 component: ProtocolComponent {
@@ -423,6 +525,7 @@ mod tests {
                 token_in: weth.clone(),
                 token_out: dai.clone(),
                 split: 0.3,
+                executor_address: None,
             },
             Swap {
                 component: ProtocolComponent {
@@ -433,6 +536,7 @@ mod tests {
                 token_in: weth.clone(),
                 token_out: dai.clone(),
                 split: 0.0, // Remainder (20%)
+                executor_address: None,
             },
         ];
         assert!(validator
@@ -440,6 +544,48 @@ mod tests {
             .is_ok());
     }
 
+    #[test]
+    fn test_validate_swaps_splits_not_summing_to_one() {
+        let validator = SplitSwapValidator;
+        let weth = Bytes::from_str("0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2").unwrap();
+        let dai = Bytes::from_str("0x6b175474e89094c44da98b954eedeac495271d0f").unwrap();
+        let usdc = Bytes::from_str("0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48").unwrap();
+
+        // 90% of the WETH is swapped and there is no remainder swap for the other 10%
+        let swaps = vec![
+            Swap {
+                component: ProtocolComponent {
+                    id: "pool1".to_string(),
+                    protocol_system: "uniswap_v2".to_string(),
+                    ..Default::default()
+                },
+                token_in: weth.clone(),
+                token_out: dai,
+                split: 0.5,
+                executor_address: None,
+            },
+            Swap {
+                component: ProtocolComponent {
+                    id: "pool2".to_string(),
+                    protocol_system: "uniswap_v2".to_string(),
+                    ..Default::default()
+                },
+                token_in: weth.clone(),
+                token_out: usdc,
+                split: 0.4,
+                executor_address: None,
+            },
+        ];
+        assert_eq!(
+            validator.validate_split_percentages(&swaps),
+            Err(EncodingError::InvalidInput(format!(
+                "Token {:?} must have exactly one 0% split for remainder handling, splits sum to \
+                 90%",
+                weth
+            )))
+        );
+    }
+
     #[test]
     fn test_validate_swaps_no_remainder_split() {
         let validator = SplitSwapValidator;
@@ -456,6 +602,7 @@ mod tests {
                 token_in: weth.clone(),
                 token_out: dai.clone(),
                 split: 0.7,
+                executor_address: None,
             },
             Swap {
                 component: ProtocolComponent {
@@ -466,6 +613,7 @@ mod tests {
                 token_in: weth.clone(),
                 token_out: dai.clone(),
                 split: 0.3,
+                executor_address: None,
             },
         ];
         assert!(matches!(
@@ -490,6 +638,7 @@ mod tests {
                 token_in: weth.clone(),
                 token_out: dai.clone(),
                 split: 0.0,
+                executor_address: None,
             },
             Swap {
                 component: ProtocolComponent {
@@ -500,6 +649,7 @@ mod tests {
                 token_in: weth.clone(),
                 token_out: dai.clone(),
                 split: 0.5,
+                executor_address: None,
             },
         ];
         assert!(matches!(
@@ -524,6 +674,7 @@ mod tests {
                 token_in: weth.clone(),
                 token_out: dai.clone(),
                 split: 0.6,
+                executor_address: None,
             },
             Swap {
                 component: ProtocolComponent {
@@ -534,6 +685,7 @@ mod tests {
                 token_in: weth.clone(),
                 token_out: dai.clone(),
                 split: 0.5,
+                executor_address: None,
             },
             Swap {
                 component: ProtocolComponent {
@@ -544,11 +696,12 @@ mod tests {
                 token_in: weth.clone(),
                 token_out: dai.clone(),
                 split: 0.0,
+                executor_address: None,
             },
         ];
         assert!(matches!(
             validator.validate_split_percentages(&invalid_overflow_swaps),
-            Err(EncodingError::InvalidInput(msg)) if msg.contains("must be <100%")
+            Err(EncodingError::InvalidSplit { token, .. }) if token == weth
         ));
     }
 
@@ -568,6 +721,7 @@ mod tests {
             token_in: weth.clone(),
             token_out: usdc.clone(),
             split: 0f64,
+            executor_address: None,
         }];
 
         let result = validator.validate_swap_path(
@@ -597,6 +751,7 @@ mod tests {
             token_in: usdc.clone(),
             token_out: weth.clone(),
             split: 0f64,
+            executor_address: None,
         }];
 
         let result = validator.validate_swap_path(
@@ -610,6 +765,41 @@ mod tests {
         assert_eq!(result, Ok(()));
     }
 
+    #[rstest]
+    #[case::unset(None, Ok(()))]
+    #[case::zero(Some(0.0), Ok(()))]
+    #[case::max(Some(0.5), Ok(()))]
+    #[case::negative(
+        Some(-0.01),
+        Err(EncodingError::InvalidInput("Slippage must be between 0 and 0.5, got -0.01".to_string()))
+    )]
+    #[case::above_max(
+        Some(1.0),
+        Err(EncodingError::InvalidInput("Slippage must be between 0 and 0.5, got 1".to_string()))
+    )]
+    #[case::nan(
+        Some(f64::NAN),
+        Err(EncodingError::InvalidInput("Slippage must be between 0 and 0.5, got NaN".to_string()))
+    )]
+    fn test_validate_slippage(
+        #[case] slippage: Option<f64>,
+        #[case] expected: Result<(), EncodingError>,
+    ) {
+        let validator = SplitSwapValidator;
+        assert_eq!(validator.validate_slippage(slippage, 0.5), expected);
+    }
+
+    #[test]
+    fn test_validate_slippage_capped_max() {
+        let validator = SplitSwapValidator;
+        assert_eq!(
+            validator.validate_slippage(Some(1.5), 2.0),
+            Err(EncodingError::InvalidInput(
+                "Slippage must be between 0 and 1, got 1.5".to_string()
+            ))
+        );
+    }
+
     #[rstest]
     #[case::slippage_with_expected_amount_set(
         Some(0.01),
@@ -668,6 +858,7 @@ mod tests {
             token_in: weth.clone(),
             token_out: usdc.clone(),
             split: 0f64,
+            executor_address: None,
         };
 
         let solution = Solution {
@@ -685,4 +876,80 @@ mod tests {
         let result = validator.validate_solution_min_amounts(&solution);
         assert_eq!(result, expected_result);
     }
+
+    #[test]
+    fn test_validate_swap_tokens() {
+        let validator = SplitSwapValidator;
+        let weth = Bytes::from_str("0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2").unwrap();
+        let dai = Bytes::from_str("0x6b175474e89094c44da98b954eedeac495271d0f").unwrap();
+        let usdc = Bytes::from_str("0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48").unwrap();
+        let mut swap = Swap {
+            component: ProtocolComponent {
+                id: "0xA478c2975Ab1Ea89e8196811F51A7B7Ade33eB11".to_string(),
+                protocol_system: "uniswap_v2".to_string(),
+                tokens: vec![weth.clone(), dai.clone()],
+                ..Default::default()
+            },
+            token_in: weth.clone(),
+            token_out: dai.clone(),
+            split: 0f64,
+            executor_address: None,
+        };
+        assert_eq!(validator.validate_swap_tokens(&[swap.clone()]), Ok(()));
+
+        swap.token_out = usdc.clone();
+        assert_eq!(
+            validator.validate_swap_tokens(&[swap]),
+            Err(EncodingError::InvalidInput(format!(
+                "Token {:?} not found in the tokens of component \
+                 0xA478c2975Ab1Ea89e8196811F51A7B7Ade33eB11",
+                usdc
+            )))
+        );
+    }
+
+    #[test]
+    fn test_validate_listed_swap_tokens() {
+        let validator = SplitSwapValidator;
+        let weth = Bytes::from_str("0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2").unwrap();
+        let dai = Bytes::from_str("0x6b175474e89094c44da98b954eedeac495271d0f").unwrap();
+        let usdc = Bytes::from_str("0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48").unwrap();
+        let unlisted_swap = Swap {
+            component: ProtocolComponent {
+                id: "0xA478c2975Ab1Ea89e8196811F51A7B7Ade33eB11".to_string(),
+                protocol_system: "uniswap_v2".to_string(),
+                ..Default::default()
+            },
+            token_in: weth.clone(),
+            token_out: dai.clone(),
+            split: 0f64,
+            executor_address: None,
+        };
+        let mut listed_swap = Swap {
+            component: ProtocolComponent {
+                id: "0xAE461cA67B15dc8dc81CE7615e0320dA1A9aB8D5".to_string(),
+                protocol_system: "uniswap_v2".to_string(),
+                tokens: vec![dai.clone(), usdc.clone()],
+                ..Default::default()
+            },
+            token_in: dai.clone(),
+            token_out: usdc.clone(),
+            split: 0f64,
+            executor_address: None,
+        };
+        assert_eq!(
+            validator.validate_listed_swap_tokens(&[unlisted_swap.clone(), listed_swap.clone()]),
+            Ok(())
+        );
+
+        listed_swap.token_out = weth.clone();
+        assert_eq!(
+            validator.validate_listed_swap_tokens(&[unlisted_swap, listed_swap]),
+            Err(EncodingError::InvalidInput(format!(
+                "Token {:?} not found in the tokens of component \
+                 0xAE461cA67B15dc8dc81CE7615e0320dA1A9aB8D5",
+                weth
+            )))
+        );
+    }
 }
@@ -1,7 +1,11 @@
 use crate::encoding::{
     errors::EncodingError,
-    evm::swap_encoder::swap_encoders::{
-        BalancerV2SwapEncoder, UniswapV2SwapEncoder, UniswapV3SwapEncoder, UniswapV4SwapEncoder,
+    evm::{
+        constants::UNISWAP_V2_FORK_PREFIX,
+        swap_encoder::swap_encoders::{
+            BalancerV2SwapEncoder, UniswapV2ForkSwapEncoder, UniswapV2SwapEncoder,
+            UniswapV3SwapEncoder, UniswapV4SwapEncoder,
+        },
     },
     swap_encoder::SwapEncoder,
 };
@@ -22,16 +26,20 @@ impl SwapEncoderBuilder {
 
     pub fn build(self) -> Result<Box<dyn SwapEncoder>, EncodingError> {
         match self.protocol_system.as_str() {
-            "uniswap_v2" => Ok(Box::new(UniswapV2SwapEncoder::new(self.executor_address))),
-            "sushiswap_v2" => Ok(Box::new(UniswapV2SwapEncoder::new(self.executor_address))),
-            "pancakeswap_v2" => Ok(Box::new(UniswapV2SwapEncoder::new(self.executor_address))),
+            // Forks share the encoder of the original protocol, with their own executor address.
+            "uniswap_v2" | "sushiswap_v2" | "pancakeswap_v2" => {
+                Ok(Box::new(UniswapV2SwapEncoder::new(self.executor_address)))
+            }
             "vm:balancer_v2" => Ok(Box::new(BalancerV2SwapEncoder::new(self.executor_address))),
-            "uniswap_v3" => Ok(Box::new(UniswapV3SwapEncoder::new(self.executor_address))),
+            "uniswap_v3" | "sushiswap_v3" | "pancakeswap_v3" => {
+                Ok(Box::new(UniswapV3SwapEncoder::new(self.executor_address)))
+            }
             "uniswap_v4" => Ok(Box::new(UniswapV4SwapEncoder::new(self.executor_address))),
-            _ => Err(EncodingError::FatalError(format!(
-                "Unknown protocol system: {}",
-                self.protocol_system
-            ))),
+            // Any other Uniswap V2 fork, configured under its prefixed protocol system
+            protocol_system if protocol_system.starts_with(UNISWAP_V2_FORK_PREFIX) => {
+                Ok(Box::new(UniswapV2ForkSwapEncoder::new(self.executor_address)))
+            }
+            _ => Err(EncodingError::UnknownProtocol { protocol_system: self.protocol_system }),
         }
     }
 }
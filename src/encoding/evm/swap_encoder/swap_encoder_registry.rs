@@ -1,17 +1,31 @@
-use std::{collections::HashMap, fs};
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    str::FromStr,
+    sync::Arc,
+};
+
+use tycho_core::Bytes;
 
 use crate::encoding::{
     errors::EncodingError,
-    evm::{constants::DEFAULT_EXECUTORS_JSON, swap_encoder::builder::SwapEncoderBuilder},
+    evm::{
+        constants::{DEFAULT_EXECUTORS_JSON, UNISWAP_V2_FORK_PREFIX},
+        swap_encoder::builder::SwapEncoderBuilder,
+    },
     models::Chain,
     swap_encoder::SwapEncoder,
 };
 
 /// Registry containing all supported `SwapEncoders`.
+///
+/// The encoders are shared between clones of the registry, so cloning it is cheap.
 #[derive(Clone)]
 pub struct SwapEncoderRegistry {
     /// A hashmap containing the protocol system as a key and the `SwapEncoder` as a value.
-    encoders: HashMap<String, Box<dyn SwapEncoder>>,
+    encoders: Arc<HashMap<String, Box<dyn SwapEncoder>>>,
+    /// `SwapEncoders` registered at runtime. They take precedence over the ones in `encoders`.
+    custom_encoders: Arc<HashMap<String, Box<dyn SwapEncoder>>>,
 }
 
 impl SwapEncoderRegistry {
@@ -21,7 +35,6 @@ impl SwapEncoderRegistry {
         executors_file_path: Option<String>,
         blockchain: tycho_core::models::Chain,
     ) -> Result<Self, EncodingError> {
-        let chain = Chain::from(blockchain);
         let config_str = if let Some(ref path) = executors_file_path {
             fs::read_to_string(path).map_err(|e| {
                 EncodingError::FatalError(format!(
@@ -32,22 +45,292 @@ impl SwapEncoderRegistry {
         } else {
             DEFAULT_EXECUTORS_JSON.to_string()
         };
-        let config: HashMap<String, HashMap<String, String>> = serde_json::from_str(&config_str)?;
+        Self::from_json(&config_str, blockchain)
+    }
+
+    /// Populates the registry with the `SwapEncoders` for the given blockchain from a JSON string
+    /// with the same structure as the executors file.
+    ///
+    /// Pools of Uniswap V2 forks without a dedicated encoder can be configured under their
+    /// protocol system prefixed with `uniswap_v2_fork:`, e.g. `uniswap_v2_fork:my_fork`. They are
+    /// encoded with `UniswapV2ForkSwapEncoder` and registered under the protocol system without the
+    /// prefix.
+    ///
+    /// Returns an error if any executor address of the chain is not a 20 bytes long hex address,
+    /// if a fork key has no protocol system after the prefix, or if a fork is configured under the
+    /// same protocol system as another executor.
+    pub fn from_json(
+        executors_json: &str,
+        blockchain: tycho_core::models::Chain,
+    ) -> Result<Self, EncodingError> {
+        let chain = Chain::from(blockchain);
+        let config: HashMap<String, HashMap<String, String>> =
+            serde_json::from_str(executors_json)?;
         let mut encoders = HashMap::new();
         let executors = config
             .get(&chain.name)
             .ok_or(EncodingError::FatalError("No executors found for chain".to_string()))?;
         for (protocol, executor_address) in executors {
+            match Bytes::from_str(executor_address) {
+                Ok(address) if address.len() == 20 => {}
+                _ => {
+                    return Err(EncodingError::InvalidInput(format!(
+                        "Invalid executor address for {}: {}",
+                        protocol, executor_address
+                    )))
+                }
+            }
+            // Uniswap V2 forks are configured as `uniswap_v2_fork:<protocol system>`
+            let protocol_system = protocol
+                .strip_prefix(UNISWAP_V2_FORK_PREFIX)
+                .unwrap_or(protocol);
+            if protocol_system.is_empty() {
+                return Err(EncodingError::InvalidInput(format!(
+                    "Missing protocol system in executor key {}",
+                    protocol
+                )));
+            }
+            if encoders.contains_key(protocol_system) {
+                return Err(EncodingError::InvalidInput(format!(
+                    "More than one executor is configured for protocol system {}",
+                    protocol_system
+                )));
+            }
             let builder = SwapEncoderBuilder::new(protocol, executor_address);
             let encoder = builder.build()?;
-            encoders.insert(protocol.to_string(), encoder);
+            encoders.insert(protocol_system.to_string(), encoder);
+        }
+
+        Ok(Self { encoders: Arc::new(encoders), custom_encoders: Arc::new(HashMap::new()) })
+    }
+
+    /// Registers a custom `SwapEncoder` for the given protocol system. It takes precedence over
+    /// the encoder built from the executors file for the same protocol system, if any.
+    ///
+    /// Returns an error if an encoder was already registered for the protocol system.
+    pub fn register(
+        &mut self,
+        protocol_system: String,
+        encoder: Box<dyn SwapEncoder>,
+    ) -> Result<(), EncodingError> {
+        if self
+            .custom_encoders
+            .contains_key(&protocol_system)
+        {
+            return Err(EncodingError::InvalidInput(format!(
+                "A swap encoder is already registered for protocol system {}",
+                protocol_system
+            )));
         }
+        Arc::make_mut(&mut self.custom_encoders).insert(protocol_system, encoder);
+        Ok(())
+    }
 
-        Ok(Self { encoders })
+    /// Returns the protocol systems with a registered `SwapEncoder`, sorted by name.
+    pub fn supported_protocols(&self) -> Vec<String> {
+        let mut protocols: Vec<String> = self
+            .encoders
+            .keys()
+            .chain(self.custom_encoders.keys())
+            .cloned()
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+        protocols.sort();
+        protocols
     }
 
     #[allow(clippy::borrowed_box)]
     pub fn get_encoder(&self, protocol_system: &str) -> Option<&Box<dyn SwapEncoder>> {
+        if let Some(encoder) = self
+            .custom_encoders
+            .get(protocol_system)
+        {
+            trace_debug!(protocol_system, "Using the swap encoder registered at runtime");
+            return Some(encoder);
+        }
         self.encoders.get(protocol_system)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use tycho_core::models::Chain as TychoCoreChain;
+
+    use super::*;
+    use crate::encoding::evm::swap_encoder::swap_encoders::UniswapV2SwapEncoder;
+
+    #[test]
+    fn test_forks_use_their_own_executor() {
+        let executors_file_path =
+            std::env::temp_dir().join("test_forks_use_their_own_executor.json");
+        fs::write(
+            &executors_file_path,
+            r#"{
+                "ethereum": {
+                    "uniswap_v3": "0x1111111111111111111111111111111111111111",
+                    "sushiswap_v3": "0x2222222222222222222222222222222222222222",
+                    "pancakeswap_v3": "0x3333333333333333333333333333333333333333"
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let registry = SwapEncoderRegistry::new(
+            Some(
+                executors_file_path
+                    .to_string_lossy()
+                    .to_string(),
+            ),
+            TychoCoreChain::Ethereum,
+        )
+        .unwrap();
+
+        for (protocol_system, executor_address) in [
+            ("uniswap_v3", "0x1111111111111111111111111111111111111111"),
+            ("sushiswap_v3", "0x2222222222222222222222222222222222222222"),
+            ("pancakeswap_v3", "0x3333333333333333333333333333333333333333"),
+        ] {
+            let encoder = registry
+                .get_encoder(protocol_system)
+                .unwrap();
+            assert_eq!(encoder.executor_address(), executor_address);
+        }
+    }
+
+    #[test]
+    fn test_from_json() {
+        let registry = SwapEncoderRegistry::from_json(
+            r#"{"ethereum": {"uniswap_v2": "0x1111111111111111111111111111111111111111"}}"#,
+            TychoCoreChain::Ethereum,
+        )
+        .unwrap();
+
+        assert_eq!(registry.supported_protocols(), vec!["uniswap_v2".to_string()]);
+    }
+
+    #[test]
+    fn test_from_json_uniswap_v2_fork() {
+        let registry = SwapEncoderRegistry::from_json(
+            r#"{"ethereum": {"uniswap_v2_fork:my_fork": "0x1111111111111111111111111111111111111111"}}"#,
+            TychoCoreChain::Ethereum,
+        )
+        .unwrap();
+
+        assert_eq!(registry.supported_protocols(), vec!["my_fork".to_string()]);
+        let encoder = registry.get_encoder("my_fork").unwrap();
+        assert_eq!(encoder.executor_address(), "0x1111111111111111111111111111111111111111");
+    }
+
+    #[test]
+    fn test_from_json_uniswap_v2_fork_duplicate() {
+        let result = SwapEncoderRegistry::from_json(
+            r#"{"ethereum": {
+                "uniswap_v2_fork:uniswap_v2": "0x1111111111111111111111111111111111111111",
+                "uniswap_v2": "0x2222222222222222222222222222222222222222"
+            }}"#,
+            TychoCoreChain::Ethereum,
+        );
+
+        assert!(matches!(result, Err(EncodingError::InvalidInput(_))));
+    }
+
+    #[test]
+    fn test_from_json_uniswap_v2_fork_empty_name() {
+        let result = SwapEncoderRegistry::from_json(
+            r#"{"ethereum": {"uniswap_v2_fork:": "0x1111111111111111111111111111111111111111"}}"#,
+            TychoCoreChain::Ethereum,
+        );
+
+        assert!(matches!(result, Err(EncodingError::InvalidInput(_))));
+    }
+
+    #[test]
+    fn test_from_json_invalid_executor_address() {
+        let result = SwapEncoderRegistry::from_json(
+            r#"{"ethereum": {"uniswap_v2": "0x1234"}}"#,
+            TychoCoreChain::Ethereum,
+        );
+
+        assert!(matches!(result, Err(EncodingError::InvalidInput(_))));
+    }
+
+    #[test]
+    fn test_register_custom_encoder() {
+        let mut registry = SwapEncoderRegistry::new(None, TychoCoreChain::Ethereum).unwrap();
+        let executor_address = "0x1234567890123456789012345678901234567890";
+        registry
+            .register(
+                "uniswap_v2".to_string(),
+                Box::new(UniswapV2SwapEncoder::new(executor_address.to_string())),
+            )
+            .unwrap();
+
+        let encoder = registry
+            .get_encoder("uniswap_v2")
+            .unwrap();
+        assert_eq!(encoder.executor_address(), executor_address);
+    }
+
+    #[test]
+    fn test_supported_protocols() {
+        let mut registry = SwapEncoderRegistry::new(None, TychoCoreChain::Ethereum).unwrap();
+        let encoder =
+            UniswapV2SwapEncoder::new("0x1234567890123456789012345678901234567890".into());
+        registry
+            .register("my_amm".to_string(), Box::new(encoder.clone()))
+            .unwrap();
+        registry
+            .register("uniswap_v2".to_string(), Box::new(encoder))
+            .unwrap();
+
+        let protocols = registry.supported_protocols();
+
+        assert!(protocols.contains(&"my_amm".to_string()));
+        assert_eq!(
+            protocols
+                .iter()
+                .filter(|protocol| *protocol == "uniswap_v2")
+                .count(),
+            1
+        );
+        assert!(protocols
+            .windows(2)
+            .all(|w| w[0] < w[1]));
+    }
+
+    #[test]
+    fn test_clone_shares_encoders() {
+        let mut registry = SwapEncoderRegistry::new(None, TychoCoreChain::Ethereum).unwrap();
+        let cloned_registry = registry.clone();
+        assert!(Arc::ptr_eq(&registry.encoders, &cloned_registry.encoders));
+
+        registry
+            .register(
+                "my_amm".to_string(),
+                Box::new(UniswapV2SwapEncoder::new(
+                    "0x1234567890123456789012345678901234567890".into(),
+                )),
+            )
+            .unwrap();
+
+        assert!(registry.get_encoder("my_amm").is_some());
+        assert!(cloned_registry
+            .get_encoder("my_amm")
+            .is_none());
+    }
+
+    #[test]
+    fn test_register_duplicate_encoder() {
+        let mut registry = SwapEncoderRegistry::new(None, TychoCoreChain::Ethereum).unwrap();
+        let encoder =
+            UniswapV2SwapEncoder::new("0x1234567890123456789012345678901234567890".into());
+        registry
+            .register("my_amm".to_string(), Box::new(encoder.clone()))
+            .unwrap();
+
+        let result = registry.register("my_amm".to_string(), Box::new(encoder));
+
+        assert!(matches!(result, Err(EncodingError::InvalidInput(_))));
+    }
+}
@@ -1,6 +1,6 @@
 use std::str::FromStr;
 
-use alloy_primitives::{Address, Bytes as AlloyBytes};
+use alloy_primitives::{Address, Bytes as AlloyBytes, FixedBytes};
 use alloy_sol_types::SolValue;
 use tycho_core::Bytes;
 
@@ -46,6 +46,7 @@ impl SwapEncoder for UniswapV2SwapEncoder {
         let zero_to_one = Self::get_zero_to_one(token_in_address, token_out_address);
         let component_id = Address::from_str(&swap.component.id)
             .map_err(|_| EncodingError::FatalError("Invalid USV2 component id".to_string()))?;
+        trace_debug!(pool = %swap.component.id, zero_to_one, "Encoding Uniswap V2 swap");
 
         // Token in address is always needed to perform a manual transfer from the router,
         // since no optimizations are performed that send from one pool to the next
@@ -63,6 +64,86 @@ impl SwapEncoder for UniswapV2SwapEncoder {
         &self.executor_address
     }
 
+    fn gas_estimate(&self) -> u64 {
+        120_000
+    }
+
+    fn clone_box(&self) -> Box<dyn SwapEncoder> {
+        Box::new(self.clone())
+    }
+}
+
+/// Encodes a swap on a pool of a Uniswap V2 fork through the given executor address.
+///
+/// Forks share the pair interface of Uniswap V2, so the swap is encoded with the layout of
+/// `UniswapV2SwapEncoder` and executed by a `UniswapV2Executor` deployed for the fork. That
+/// executor computes the amount out with the 30 bps fee of Uniswap V2, which a pair charging up to
+/// 30 bps accepts. The fee is read from the optional `fee_bps` static attribute, in basis points,
+/// and pools charging more are rejected instead of reverting on chain.
+///
+/// The encoder isn't bound to a protocol system: it's built for any executors config key prefixed
+/// with `uniswap_v2_fork:` (see `UNISWAP_V2_FORK_PREFIX`), and can be registered under any name
+/// with `SwapEncoderRegistry::register`.
+///
+/// # Fields
+/// * `executor_address` - The address of the executor contract that will perform the swap.
+#[derive(Clone)]
+pub struct UniswapV2ForkSwapEncoder {
+    executor_address: String,
+}
+
+impl UniswapV2ForkSwapEncoder {
+    const MAX_FEE_BPS: u16 = 30;
+
+    fn validate_fee_bps(swap: &Swap) -> Result<(), EncodingError> {
+        let Some(fee_bps) = swap
+            .component
+            .static_attributes
+            .get("fee_bps")
+        else {
+            return Ok(());
+        };
+        if fee_bps.len() > 2 {
+            return Err(EncodingError::InvalidInput(format!(
+                "Invalid fee_bps of Uniswap V2 fork pool {}: {:?}",
+                swap.component.id, fee_bps
+            )));
+        }
+        let fee_bps = u16::from_be_bytes(pad_to_fixed_size::<2>(fee_bps)?);
+        if fee_bps > Self::MAX_FEE_BPS {
+            return Err(EncodingError::InvalidInput(format!(
+                "Unsupported fee_bps of Uniswap V2 fork pool {}: {} is above {}",
+                swap.component.id,
+                fee_bps,
+                Self::MAX_FEE_BPS
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl SwapEncoder for UniswapV2ForkSwapEncoder {
+    fn new(executor_address: String) -> Self {
+        Self { executor_address }
+    }
+
+    fn encode_swap(
+        &self,
+        swap: Swap,
+        encoding_context: EncodingContext,
+    ) -> Result<Vec<u8>, EncodingError> {
+        Self::validate_fee_bps(&swap)?;
+        UniswapV2SwapEncoder::new(self.executor_address.clone()).encode_swap(swap, encoding_context)
+    }
+
+    fn executor_address(&self) -> &str {
+        &self.executor_address
+    }
+
+    fn gas_estimate(&self) -> u64 {
+        120_000
+    }
+
     fn clone_box(&self) -> Box<dyn SwapEncoder> {
         Box::new(self.clone())
     }
@@ -104,6 +185,12 @@ impl SwapEncoder for UniswapV3SwapEncoder {
 
         let pool_fee_u24 = pad_to_fixed_size::<3>(&pool_fee_bytes)
             .map_err(|_| EncodingError::FatalError("Failed to extract fee bytes".to_string()))?;
+        trace_debug!(
+            pool = %swap.component.id,
+            fee = ?pool_fee_u24,
+            zero_to_one,
+            "Encoding Uniswap V3 swap"
+        );
 
         let args = (
             token_in_address,
@@ -120,6 +207,10 @@ impl SwapEncoder for UniswapV3SwapEncoder {
     fn executor_address(&self) -> &str {
         &self.executor_address
     }
+
+    fn gas_estimate(&self) -> u64 {
+        160_000
+    }
     fn clone_box(&self) -> Box<dyn SwapEncoder> {
         Box::new(self.clone())
     }
@@ -127,6 +218,9 @@ impl SwapEncoder for UniswapV3SwapEncoder {
 
 /// Encodes a swap on a Uniswap V4 pool through the given executor address.
 ///
+/// The pool key is built by the executor from the swap tokens and the `key_lp_fee` and
+/// `tick_spacing` static attributes. Pools with hooks are not supported.
+///
 /// # Fields
 /// * `executor_address` - The address of the executor contract that will perform the swap.
 /// * `swap_selector` - The selector of the swap function in the executor contract.
@@ -163,6 +257,29 @@ impl SwapEncoder for UniswapV4SwapEncoder {
             EncodingError::FatalError("Failed to pad tick spacing bytes".to_string())
         })?;
 
+        // The executor builds the pool key without hooks, so a pool with hooks would be a
+        // different pool than the one the solution was computed for
+        if let Some(hooks) = swap
+            .component
+            .static_attributes
+            .get("hooks")
+        {
+            if hooks.iter().any(|byte| *byte != 0) {
+                return Err(EncodingError::InvalidInput(format!(
+                    "Uniswap V4 pools with hooks are not supported. Pool {} uses hooks {:?}",
+                    swap.component.id, hooks
+                )));
+            }
+        }
+
+        trace_debug!(
+            pool = %swap.component.id,
+            fee = ?pool_fee_u24,
+            tick_spacing = ?pool_tick_spacing_u24,
+            first_swap = encoding_context.group_token_in == swap.token_in,
+            "Encoding Uniswap V4 swap"
+        );
+
         // Early check if this is not the first swap
         if encoding_context.group_token_in != swap.token_in {
             return Ok((bytes_to_address(&swap.token_out)?, pool_fee_u24, pool_tick_spacing_u24)
@@ -199,6 +316,10 @@ impl SwapEncoder for UniswapV4SwapEncoder {
         &self.executor_address
     }
 
+    fn gas_estimate(&self) -> u64 {
+        200_000
+    }
+
     fn clone_box(&self) -> Box<dyn SwapEncoder> {
         Box::new(self.clone())
     }
@@ -206,6 +327,9 @@ impl SwapEncoder for UniswapV4SwapEncoder {
 
 /// Encodes a swap on a Balancer V2 pool through the given executor address.
 ///
+/// The executor performs a single pool `GIVEN_IN` swap on the vault with empty user data, so exact
+/// out swaps are rejected.
+///
 /// # Fields
 /// * `executor_address` - The address of the executor contract that will perform the swap.
 /// * `vault_address` - The address of the vault contract that will perform the swap.
@@ -215,6 +339,29 @@ pub struct BalancerV2SwapEncoder {
     vault_address: String,
 }
 
+impl BalancerV2SwapEncoder {
+    /// Gets the 32 bytes pool id from the `pool_id` static attribute, falling back to the
+    /// component id.
+    fn get_pool_id(swap: &Swap) -> Result<FixedBytes<32>, EncodingError> {
+        let pool_id = match swap
+            .component
+            .static_attributes
+            .get("pool_id")
+        {
+            Some(pool_id) => AlloyBytes::copy_from_slice(pool_id),
+            None => AlloyBytes::from_str(&swap.component.id)
+                .map_err(|_| EncodingError::FatalError("Invalid component ID".to_string()))?,
+        };
+        if pool_id.len() != 32 {
+            return Err(EncodingError::InvalidInput(format!(
+                "Invalid Balancer V2 pool id: expected 32 bytes, got {}",
+                pool_id.len()
+            )));
+        }
+        Ok(FixedBytes::<32>::from_slice(&pool_id))
+    }
+}
+
 impl SwapEncoder for BalancerV2SwapEncoder {
     fn new(executor_address: String) -> Self {
         Self {
@@ -227,6 +374,11 @@ impl SwapEncoder for BalancerV2SwapEncoder {
         swap: Swap,
         encoding_context: EncodingContext,
     ) -> Result<Vec<u8>, EncodingError> {
+        if encoding_context.exact_out {
+            return Err(EncodingError::UnsupportedExactOut);
+        }
+        let pool_id = Self::get_pool_id(&swap)?;
+
         let token_approvals_manager = ProtocolApprovalsManager::new()?;
         let token = bytes_to_address(&swap.token_in)?;
         let router_address = bytes_to_address(&encoding_context.router_address)?;
@@ -236,14 +388,12 @@ impl SwapEncoder for BalancerV2SwapEncoder {
             Address::from_str(&self.vault_address)
                 .map_err(|_| EncodingError::FatalError("Invalid vault address".to_string()))?,
         )?;
-
-        let component_id = AlloyBytes::from_str(&swap.component.id)
-            .map_err(|_| EncodingError::FatalError("Invalid component ID".to_string()))?;
+        trace_debug!(?pool_id, approval_needed, "Encoding Balancer V2 swap");
 
         let args = (
             bytes_to_address(&swap.token_in)?,
             bytes_to_address(&swap.token_out)?,
-            component_id,
+            pool_id,
             bytes_to_address(&encoding_context.receiver)?,
             approval_needed,
         );
@@ -253,6 +403,10 @@ impl SwapEncoder for BalancerV2SwapEncoder {
     fn executor_address(&self) -> &str {
         &self.executor_address
     }
+
+    fn gas_estimate(&self) -> u64 {
+        200_000
+    }
     fn clone_box(&self) -> Box<dyn SwapEncoder> {
         Box::new(self.clone())
     }
@@ -264,10 +418,35 @@ mod tests {
 
     use alloy::hex::encode;
     use num_bigint::BigInt;
+    use rstest::rstest;
     use tycho_core::{models::protocol::ProtocolComponent, Bytes};
 
     use super::*;
 
+    // Generated with `makeAddr("bob")` using forge
+    const BOB: &str = "0x1d96f2f6bef1202e4ce1ff6dad0c2cb002861d3e";
+
+    fn swap(component: ProtocolComponent, token_in: &str, token_out: &str) -> Swap {
+        Swap {
+            component,
+            token_in: Bytes::from(token_in),
+            token_out: Bytes::from(token_out),
+            split: 0f64,
+            executor_address: None,
+        }
+    }
+
+    /// Context of an exact in swap that is alone in its group.
+    fn encoding_context(swap: &Swap, receiver: &str, router_address: Bytes) -> EncodingContext {
+        EncodingContext {
+            receiver: Bytes::from(receiver),
+            exact_out: false,
+            router_address,
+            group_token_in: swap.token_in.clone(),
+            group_token_out: swap.token_out.clone(),
+        }
+    }
+
     #[test]
     fn test_encode_uniswap_v2() {
         let usv2_pool = ProtocolComponent {
@@ -275,21 +454,13 @@ mod tests {
             ..Default::default()
         };
 
-        let token_in = Bytes::from("0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2");
-        let token_out = Bytes::from("0x6b175474e89094c44da98b954eedeac495271d0f");
-        let swap = Swap {
-            component: usv2_pool,
-            token_in: token_in.clone(),
-            token_out: token_out.clone(),
-            split: 0f64,
-        };
-        let encoding_context = EncodingContext {
-            receiver: Bytes::from("0x0000000000000000000000000000000000000001"),
-            exact_out: false,
-            router_address: Bytes::zero(20),
-            group_token_in: token_in.clone(),
-            group_token_out: token_out.clone(),
-        };
+        let swap = swap(
+            usv2_pool,
+            "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2",
+            "0x6b175474e89094c44da98b954eedeac495271d0f",
+        );
+        let encoding_context =
+            encoding_context(&swap, "0x0000000000000000000000000000000000000001", Bytes::zero(20));
         let encoder =
             UniswapV2SwapEncoder::new(String::from("0x543778987b293C7E8Cf0722BB2e935ba6f4068D4"));
         let encoded_swap = encoder
@@ -322,21 +493,13 @@ mod tests {
             static_attributes,
             ..Default::default()
         };
-        let token_in = Bytes::from("0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2");
-        let token_out = Bytes::from("0x6b175474e89094c44da98b954eedeac495271d0f");
-        let swap = Swap {
-            component: usv3_pool,
-            token_in: token_in.clone(),
-            token_out: token_out.clone(),
-            split: 0f64,
-        };
-        let encoding_context = EncodingContext {
-            receiver: Bytes::from("0x0000000000000000000000000000000000000001"),
-            exact_out: false,
-            router_address: Bytes::zero(20),
-            group_token_in: token_in.clone(),
-            group_token_out: token_out.clone(),
-        };
+        let swap = swap(
+            usv3_pool,
+            "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2",
+            "0x6b175474e89094c44da98b954eedeac495271d0f",
+        );
+        let encoding_context =
+            encoding_context(&swap, "0x0000000000000000000000000000000000000001", Bytes::zero(20));
         let encoder =
             UniswapV3SwapEncoder::new(String::from("0x543778987b293C7E8Cf0722BB2e935ba6f4068D4"));
         let encoded_swap = encoder
@@ -369,22 +532,12 @@ mod tests {
             protocol_system: String::from("vm:balancer_v2"),
             ..Default::default()
         };
-        let token_in = Bytes::from("0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2");
-        let token_out = Bytes::from("0xba100000625a3754423978a60c9317c58a424e3D");
-        let swap = Swap {
-            component: balancer_pool,
-            token_in: token_in.clone(),
-            token_out: token_out.clone(),
-            split: 0f64,
-        };
-        let encoding_context = EncodingContext {
-            // The receiver was generated with `makeAddr("bob") using forge`
-            receiver: Bytes::from("0x1d96f2f6bef1202e4ce1ff6dad0c2cb002861d3e"),
-            exact_out: false,
-            router_address: Bytes::zero(20),
-            group_token_in: token_in.clone(),
-            group_token_out: token_out.clone(),
-        };
+        let swap = swap(
+            balancer_pool,
+            "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2",
+            "0xba100000625a3754423978a60c9317c58a424e3D",
+        );
+        let encoding_context = encoding_context(&swap, BOB, Bytes::zero(20));
         let encoder =
             BalancerV2SwapEncoder::new(String::from("0x543778987b293C7E8Cf0722BB2e935ba6f4068D4"));
         let encoded_swap = encoder
@@ -409,12 +562,68 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_balancer_v2_pool_id_from_static_attributes() {
+        let pool_id =
+            Bytes::from("0x5c6ee304399dbdb9c8ef030ab642b10820db8f56000200000000000000000014");
+        let balancer_pool = ProtocolComponent {
+            id: String::from("0x5c6ee304399dbdb9c8ef030ab642b10820db8f56"),
+            static_attributes: HashMap::from([("pool_id".to_string(), pool_id.clone())]),
+            ..Default::default()
+        };
+        let swap = swap(
+            balancer_pool,
+            "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2",
+            "0xba100000625a3754423978a60c9317c58a424e3D",
+        );
+
+        let result = BalancerV2SwapEncoder::get_pool_id(&swap).unwrap();
+
+        assert_eq!(result.to_vec(), pool_id.to_vec());
+    }
+
+    #[test]
+    fn test_balancer_v2_invalid_pool_id() {
+        let balancer_pool = ProtocolComponent {
+            id: String::from("0x5c6ee304399dbdb9c8ef030ab642b10820db8f56"),
+            ..Default::default()
+        };
+        let swap = swap(
+            balancer_pool,
+            "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2",
+            "0xba100000625a3754423978a60c9317c58a424e3D",
+        );
+
+        let result = BalancerV2SwapEncoder::get_pool_id(&swap);
+
+        assert!(matches!(result, Err(EncodingError::InvalidInput(_))));
+    }
+
+    #[test]
+    fn test_encode_balancer_v2_exact_out_fails() {
+        let balancer_pool = ProtocolComponent {
+            id: String::from("0x5c6ee304399dbdb9c8ef030ab642b10820db8f56000200000000000000000014"),
+            protocol_system: String::from("vm:balancer_v2"),
+            ..Default::default()
+        };
+        let swap = swap(
+            balancer_pool,
+            "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2",
+            "0xba100000625a3754423978a60c9317c58a424e3D",
+        );
+        let encoding_context =
+            EncodingContext { exact_out: true, ..encoding_context(&swap, BOB, Bytes::zero(20)) };
+        let encoder =
+            BalancerV2SwapEncoder::new(String::from("0x543778987b293C7E8Cf0722BB2e935ba6f4068D4"));
+        let result = encoder.encode_swap(swap, encoding_context);
+
+        assert_eq!(result, Err(EncodingError::UnsupportedExactOut));
+    }
+
     #[test]
     fn test_encode_uniswap_v4_simple_swap() {
         let fee = BigInt::from(100);
         let tick_spacing = BigInt::from(1);
-        let token_in = Bytes::from("0x4c9EDD5852cd905f086C759E8383e09bff1E68B3"); // USDE
-        let token_out = Bytes::from("0xdAC17F958D2ee523a2206206994597C13D831ec7"); // USDT
 
         let mut static_attributes: HashMap<String, Bytes> = HashMap::new();
         static_attributes.insert("key_lp_fee".into(), Bytes::from(fee.to_signed_bytes_be()));
@@ -427,23 +636,19 @@ mod tests {
             static_attributes,
             ..Default::default()
         };
-        let swap = Swap {
-            component: usv4_pool,
-            token_in: token_in.clone(),
-            token_out: token_out.clone(),
-            split: 0f64,
-        };
-        let encoding_context = EncodingContext {
+        let swap = swap(
+            usv4_pool,
+            "0x4c9EDD5852cd905f086C759E8383e09bff1E68B3", // USDE
+            "0xdAC17F958D2ee523a2206206994597C13D831ec7", // USDT
+        );
+        let encoding_context = encoding_context(
+            &swap,
             // The receiver address was taken from `address(uniswapV4Exposed)` in the
             // UniswapV4Executor.t.sol
-            receiver: Bytes::from("0x5615deb798bb3e4dfa0139dfa1b3d433cc23b72f"),
-            exact_out: false,
+            "0x5615deb798bb3e4dfa0139dfa1b3d433cc23b72f",
             // Same as the executor address
-            router_address: Bytes::from("0x5615deb798bb3e4dfa0139dfa1b3d433cc23b72f"),
-
-            group_token_in: token_in.clone(),
-            group_token_out: token_out.clone(),
-        };
+            Bytes::from("0x5615deb798bb3e4dfa0139dfa1b3d433cc23b72f"),
+        );
         let encoder =
             UniswapV4SwapEncoder::new(String::from("0xF62849F9A0B5Bf2913b396098F7c7019b51A820a"));
         let encoded_swap = encoder
@@ -479,8 +684,6 @@ mod tests {
         let fee = BigInt::from(3000);
         let tick_spacing = BigInt::from(60);
         let group_token_in = Bytes::from("0x4c9EDD5852cd905f086C759E8383e09bff1E68B3"); // USDE
-        let token_in = Bytes::from("0xdAC17F958D2ee523a2206206994597C13D831ec7"); // USDT
-        let token_out = Bytes::from("0x2260FAC5E5542a773Aa44fBCfeDf7C193bc2C599"); // WBTC
 
         let mut static_attributes: HashMap<String, Bytes> = HashMap::new();
         static_attributes.insert("key_lp_fee".into(), Bytes::from(fee.to_signed_bytes_be()));
@@ -493,20 +696,16 @@ mod tests {
             ..Default::default()
         };
 
-        let swap = Swap {
-            component: usv4_pool,
-            token_in: token_in.clone(),
-            token_out: token_out.clone(),
-            split: 0f64,
-        };
+        let swap = swap(
+            usv4_pool,
+            "0xdAC17F958D2ee523a2206206994597C13D831ec7", // USDT
+            "0x2260FAC5E5542a773Aa44fBCfeDf7C193bc2C599", // WBTC
+        );
 
+        // Token out is the same as the group token out
         let encoding_context = EncodingContext {
-            receiver: Bytes::from("0x0000000000000000000000000000000000000001"),
-            exact_out: false,
-            router_address: Bytes::zero(20),
-            group_token_in: group_token_in.clone(),
-            // Token out is the same as the group token out
-            group_token_out: token_out.clone(),
+            group_token_in,
+            ..encoding_context(&swap, "0x0000000000000000000000000000000000000001", Bytes::zero(20))
         };
 
         let encoder =
@@ -533,7 +732,6 @@ mod tests {
     #[test]
     fn test_encode_uniswap_v4_sequential_swap() {
         let usde_address = Bytes::from("0x4c9EDD5852cd905f086C759E8383e09bff1E68B3");
-        let usdt_address = Bytes::from("0xdAC17F958D2ee523a2206206994597C13D831ec7");
         let wbtc_address = Bytes::from("0x2260FAC5E5542a773Aa44fBCfeDf7C193bc2C599");
         let router_address = Bytes::from("0x5615deb798bb3e4dfa0139dfa1b3d433cc23b72f");
         let receiver_address = router_address.clone();
@@ -583,19 +781,16 @@ mod tests {
             ..Default::default()
         };
 
-        let initial_swap = Swap {
-            component: usde_usdt_component,
-            token_in: usde_address.clone(),
-            token_out: usdt_address.clone(),
-            split: 0f64,
-        };
-
-        let second_swap = Swap {
-            component: usdt_wbtc_component,
-            token_in: usdt_address,
-            token_out: wbtc_address.clone(),
-            split: 0f64,
-        };
+        let initial_swap = swap(
+            usde_usdt_component,
+            "0x4c9EDD5852cd905f086C759E8383e09bff1E68B3",
+            "0xdAC17F958D2ee523a2206206994597C13D831ec7",
+        );
+        let second_swap = swap(
+            usdt_wbtc_component,
+            "0xdAC17F958D2ee523a2206206994597C13D831ec7",
+            "0x2260FAC5E5542a773Aa44fBCfeDf7C193bc2C599",
+        );
 
         let encoder =
             UniswapV4SwapEncoder::new(String::from("0xF62849F9A0B5Bf2913b396098F7c7019b51A820a"));
@@ -636,4 +831,90 @@ mod tests {
             ))
         );
     }
+
+    #[test]
+    fn test_encode_uniswap_v4_with_hooks_fails() {
+        let mut static_attributes: HashMap<String, Bytes> = HashMap::new();
+        static_attributes
+            .insert("key_lp_fee".into(), Bytes::from(BigInt::from(100).to_signed_bytes_be()));
+        static_attributes
+            .insert("tick_spacing".into(), Bytes::from(BigInt::from(1).to_signed_bytes_be()));
+        static_attributes
+            .insert("hooks".into(), Bytes::from("0x0010000000000000000000000000000000000040"));
+
+        let usv4_pool = ProtocolComponent {
+            id: String::from("0x000000000004444c5dc75cB358380D2e3dE08A90"),
+            static_attributes,
+            ..Default::default()
+        };
+        let swap = swap(
+            usv4_pool,
+            "0x4c9EDD5852cd905f086C759E8383e09bff1E68B3", // USDE
+            "0xdAC17F958D2ee523a2206206994597C13D831ec7", // USDT
+        );
+        let encoding_context =
+            encoding_context(&swap, "0x0000000000000000000000000000000000000001", Bytes::zero(20));
+        let encoder =
+            UniswapV4SwapEncoder::new(String::from("0xF62849F9A0B5Bf2913b396098F7c7019b51A820a"));
+        let result = encoder.encode_swap(swap, encoding_context);
+
+        assert!(matches!(result, Err(EncodingError::InvalidInput(_))));
+    }
+
+    fn uniswap_v2_fork_swap(fee_bps: Option<&str>) -> Swap {
+        let mut static_attributes = HashMap::new();
+        if let Some(fee_bps) = fee_bps {
+            static_attributes.insert("fee_bps".to_string(), Bytes::from(fee_bps));
+        }
+        swap(
+            ProtocolComponent {
+                id: String::from("0x88e6A0c2dDD26FEEb64F039a2c41296FcB3f5640"),
+                static_attributes,
+                ..Default::default()
+            },
+            "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2",
+            "0x6b175474e89094c44da98b954eedeac495271d0f",
+        )
+    }
+
+    #[rstest]
+    #[case::default_fee(None)]
+    #[case::lower_fee(Some("0x19"))]
+    fn test_encode_uniswap_v2_fork(#[case] fee_bps: Option<&str>) {
+        let swap = uniswap_v2_fork_swap(fee_bps);
+        let encoding_context = encoding_context(&swap, BOB, Bytes::zero(20));
+        let encoder = UniswapV2ForkSwapEncoder::new(String::from(
+            "0x543778987b293C7E8Cf0722BB2e935ba6f4068D4",
+        ));
+        let encoded_swap = encoder
+            .encode_swap(swap, encoding_context)
+            .unwrap();
+        assert_eq!(
+            encode(&encoded_swap),
+            String::from(concat!(
+                // in token
+                "c02aaa39b223fe8d0a0e5c4f27ead9083c756cc2",
+                // component id
+                "88e6a0c2ddd26feeb64f039a2c41296fcb3f5640",
+                // receiver
+                "1d96f2f6bef1202e4ce1ff6dad0c2cb002861d3e",
+                // zero for one
+                "00",
+            ))
+        );
+    }
+
+    #[rstest]
+    #[case::above_uniswap_v2_fee("0x1f")]
+    #[case::too_long("0x000019")]
+    fn test_encode_uniswap_v2_fork_invalid_fee(#[case] fee_bps: &str) {
+        let swap = uniswap_v2_fork_swap(Some(fee_bps));
+        let encoding_context = encoding_context(&swap, BOB, Bytes::zero(20));
+        let encoder = UniswapV2ForkSwapEncoder::new(String::from(
+            "0x543778987b293C7E8Cf0722BB2e935ba6f4068D4",
+        ));
+
+        let result = encoder.encode_swap(swap, encoding_context);
+        assert!(matches!(result, Err(EncodingError::InvalidInput(_))));
+    }
 }
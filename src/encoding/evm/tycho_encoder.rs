@@ -1,50 +1,411 @@
-use std::collections::HashSet;
+use std::{
+    collections::HashSet,
+    time::{Duration, Instant},
+};
 
+use alloy_primitives::{Bytes as AlloyBytes, B256, U256};
+use alloy_sol_types::{sol, SolValue};
 use num_bigint::BigUint;
 use tycho_core::Bytes;
 
 use crate::encoding::{
     errors::EncodingError,
-    models::{Chain, NativeAction, Solution, Transaction},
+    evm::{
+        approvals::ApprovalAmount,
+        constants::{
+            DIRECT_EXECUTION_SIGNATURE, EIP7702_BATCH_EXECUTE_SIGNATURE, EIP7702_BATCH_MODE,
+        },
+        decoder::{describe_transaction, TransactionSummary},
+        strategy_encoder::strategy_validators::SplitSwapValidator,
+        utils::{
+            biguint_to_u256, bytes_to_address, encode_input, get_function_selector,
+            get_min_amount_for_solution,
+        },
+    },
+    models::{CalldataSize, Chain, NativeAction, Solution, Transaction},
     strategy_encoder::StrategyEncoder,
     tycho_encoder::TychoEncoder,
 };
 
+/// Metrics of an `EVMTychoEncoder::encode_router_calldata_with_meta` call.
+///
+/// # Fields
+/// * `num_swaps`: Number of swaps of all the solutions
+/// * `num_executor_calls`: Number of executor calls of all the encoded transactions. Swaps grouped
+///   on a groupable protocol (e.g. Uniswap V4) take a single call. Transactions that don't match a
+///   layout of this crate, e.g. of a custom strategy, are not counted. The calls of an EIP-7702
+///   batch are counted one by one
+/// * `encode_duration`: Time spent encoding the solutions
+#[derive(Clone, Debug, PartialEq)]
+pub struct EncodeMeta {
+    pub num_swaps: usize,
+    pub num_executor_calls: usize,
+    pub encode_duration: Duration,
+}
+
+/// Version of the layout of the calldata produced by the encoders of this crate (the router and
+/// executor methods and the encoding of the swaps). It's bumped whenever the produced calldata
+/// changes, so transactions cached by consumers can be checked against the current layout.
+pub const ENCODING_FORMAT_VERSION: u32 = 1;
+
+sol! {
+    /// A call of an ERC-7821 batch.
+    struct Call {
+        address to;
+        uint256 value;
+        bytes data;
+    }
+}
+
 /// Represents an encoder for a swap using any strategy supported by the strategy registry.
 ///
 /// # Fields
 /// * `strategy_encoder`: Strategy encoder to follow for encoding the solution
 /// * `native_address`: Address of the chain's native token
 /// * `wrapped_address`: Address of the chain's wrapped native token
+/// * `router_address`: Default router address, used for solutions that don't set one
+/// * `native_token_sentinel`: Address that represents the native token in the solutions
+/// * `approval_amount`: Amount of the ERC-20 approvals. If it's not set, Permit2 is approved for
+///   the maximum amount and the router for the given amount
+/// * `eip7702_batch`: Whether the approvals and the swap of each solution are encoded as a single
+///   batch, executed by the sender's EIP-7702 delegate
+/// * `standard_approval`: Whether the approvals of each solution are returned as separate
+///   transactions before its swap
+#[derive(Clone)]
 pub struct EVMTychoEncoder {
     strategy_encoder: Box<dyn StrategyEncoder>,
     native_address: Bytes,
     wrapped_address: Bytes,
-}
-
-impl Clone for EVMTychoEncoder {
-    fn clone(&self) -> Self {
-        Self {
-            strategy_encoder: self.strategy_encoder.clone_box(),
-            native_address: self.native_address.clone(),
-            wrapped_address: self.wrapped_address.clone(),
-        }
-    }
+    router_address: Option<Bytes>,
+    native_token_sentinel: Bytes,
+    approval_amount: Option<ApprovalAmount>,
+    eip7702_batch: bool,
+    standard_approval: bool,
 }
 
 impl EVMTychoEncoder {
     pub fn new(
         chain: tycho_core::models::Chain,
         strategy_encoder: Box<dyn StrategyEncoder>,
+        router_address: Option<Bytes>,
     ) -> Result<Self, EncodingError> {
         let chain: Chain = Chain::from(chain);
-        let native_address = chain.native_token()?;
-        let wrapped_address = chain.wrapped_token()?;
-        Ok(EVMTychoEncoder { strategy_encoder, native_address, wrapped_address })
+        let (native_address, wrapped_address) = chain.native_tokens()?;
+        Ok(EVMTychoEncoder {
+            strategy_encoder,
+            native_token_sentinel: native_address.clone(),
+            native_address,
+            wrapped_address,
+            router_address,
+            approval_amount: None,
+            eip7702_batch: false,
+            standard_approval: false,
+        })
+    }
+
+    /// Sets the address that represents the native token in the solutions, e.g.
+    /// `0xeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeee`. Defaults to the chain's native token address
+    /// (the zero address on Ethereum).
+    ///
+    /// The sentinel is replaced by the chain's native token address in the given token, the checked
+    /// token and the swaps' tokens of every solution before it's validated and encoded, so the
+    /// strategies detect the native token and the router receives the address it expects.
+    pub fn with_native_token_sentinel(mut self, native_token_sentinel: Bytes) -> Self {
+        self.native_token_sentinel = native_token_sentinel;
+        self
+    }
+
+    /// Sets whether each solution is encoded as a single batch of calls, for a sender whose account
+    /// is delegated with EIP-7702 to a contract implementing ERC-7821
+    /// (`execute(bytes32 mode, bytes executionData)`). Disabled by default.
+    ///
+    /// The batch holds the approvals of `encode_approvals` followed by the swap, so the approval
+    /// and the swap are authorized by one signature. The returned transaction is sent by the
+    /// sender to its own address, with the value of the swap.
+    ///
+    /// Only router swaps are batched. Solutions executed directly on the executor, by the direct
+    /// execution strategy or the auto strategy, fail with an `InvalidInput` error.
+    pub fn with_eip7702_batch(mut self, eip7702_batch: bool) -> Self {
+        self.eip7702_batch = eip7702_batch;
+        self
+    }
+
+    /// Sets whether `encode_router_calldata` returns the approvals of `encode_approvals` of each
+    /// solution as separate transactions, followed by the swap of the solution. Disabled by
+    /// default, in which case exactly one transaction is returned per solution.
+    ///
+    /// This is meant for the `tycho_router` strategy, whose swap transfers the given token from
+    /// the sender with `transferFrom` and so needs an `approve(router, amount)` first. Solutions
+    /// that need no approval, e.g. selling the native token, only return their swap. The option is
+    /// ignored with `with_eip7702_batch`, since the batch already holds the approvals.
+    pub fn with_standard_approval(mut self, standard_approval: bool) -> Self {
+        self.standard_approval = standard_approval;
+        self
+    }
+
+    /// Sets the amount of the ERC-20 approvals encoded by `encode_approvals`, for both the Permit2
+    /// contract and the router. If it's not set, Permit2 is approved for the maximum amount and
+    /// the router for the given amount.
+    ///
+    /// **Note**: This doesn't change the Permit2 allowance signed by the strategy, which is set
+    /// with `SplitSwapStrategyEncoder::with_approval_amount` (or
+    /// `EVMEncoderBuilder::approval_amount`, which sets both).
+    pub fn with_approval_amount(mut self, approval_amount: ApprovalAmount) -> Self {
+        self.approval_amount = Some(approval_amount);
+        self
+    }
+
+    /// Encodes the ERC-20 approval needed before executing the given solution, without the swap.
+    ///
+    /// Depending on the strategy, the given token is approved for the Permit2 contract (by default
+    /// with the maximum amount, since each swap is then authorized by its own permit) or for the
+    /// router (by default with the given amount). See `with_approval_amount` to change the amount.
+    /// No transaction is returned if no approval is needed, e.g. when selling the native token.
+    pub fn encode_approvals(&self, solution: &Solution) -> Result<Vec<Transaction>, EncodingError> {
+        let solution = self.with_default_router_address(self.with_native_token(solution.clone()));
+        let Some(spender) = self
+            .strategy_encoder
+            .get_approval_spender(&solution)?
+        else {
+            return Ok(vec![]);
+        };
+        let amount = match self.approval_amount {
+            Some(ApprovalAmount::Exact) => biguint_to_u256(&solution.given_amount),
+            Some(ApprovalAmount::Infinite) => U256::MAX,
+            None if spender == solution.router_address => biguint_to_u256(&solution.given_amount),
+            None => U256::MAX,
+        };
+        let method_signature = "approve(address,uint256)";
+        let data =
+            encode_input(method_signature, (bytes_to_address(&spender)?, amount).abi_encode());
+        Ok(vec![Transaction {
+            to: solution.given_token,
+            value: BigUint::ZERO,
+            data,
+            method: "approve".to_string(),
+            selector: get_function_selector(method_signature),
+        }])
+    }
+
+    /// Returns the minimum amount out enforced for the given solution: the one computed by the
+    /// strategy (e.g. including the min out buffer of the Tycho router strategy), or the checked
+    /// amount, or the expected amount minus the slippage, for strategies that don't check it.
+    ///
+    /// Returns an error if the slippage of the solution isn't in [0, 1].
+    pub fn min_amount_out(&self, solution: &Solution) -> Result<BigUint, EncodingError> {
+        SplitSwapValidator.validate_slippage(solution.slippage, 1.0)?;
+        Ok(self
+            .strategy_encoder
+            .min_amount_out(solution)?
+            .unwrap_or_else(|| get_min_amount_for_solution(solution.clone())))
+    }
+
+    /// Replaces the native token sentinel by the chain's native token address in the solution.
+    fn with_native_token(&self, mut solution: Solution) -> Solution {
+        if self.native_token_sentinel == self.native_address {
+            return solution;
+        }
+        let replace = |token: &mut Bytes| {
+            if *token == self.native_token_sentinel {
+                *token = self.native_address.clone();
+            }
+        };
+        replace(&mut solution.given_token);
+        replace(&mut solution.checked_token);
+        for swap in solution.swaps.iter_mut() {
+            replace(&mut swap.token_in);
+            replace(&mut swap.token_out);
+        }
+        solution
+    }
+
+    /// Sets the default router address on the solution if it doesn't set one. The router address
+    /// of the solution takes precedence over the default one.
+    fn with_default_router_address(&self, mut solution: Solution) -> Solution {
+        if solution.router_address.is_empty() {
+            if let Some(router_address) = &self.router_address {
+                solution.router_address = router_address.clone();
+            }
+        }
+        solution
+    }
+
+    /// Returns the protocol systems supported by the configured strategy, sorted by name. Solutions
+    /// with swaps on any other protocol system can't be encoded.
+    pub fn supported_protocols(&self) -> Vec<String> {
+        self.strategy_encoder
+            .supported_protocols()
+    }
+
+    /// Estimates an upper bound of the gas needed to execute the transaction encoded for the given
+    /// solution. See `StrategyEncoder::estimate_gas` for details.
+    pub fn estimate_gas(&self, solution: &Solution) -> Result<u64, EncodingError> {
+        self.strategy_encoder
+            .estimate_gas(&self.with_native_token(solution.clone()))
+    }
+
+    /// Returns the calldata size of the transaction encoded for the given solution, e.g. to prefer
+    /// routes with a smaller L1 data cost on rollups. See `CalldataSize` for details.
+    pub fn calldata_size(&self, solution: &Solution) -> Result<CalldataSize, EncodingError> {
+        Ok(self
+            .encode_solution(solution.clone())?
+            .calldata_size())
+    }
+
+    /// Encodes the solutions into transactions like `TychoEncoder::encode_router_calldata`, but
+    /// encodes the solutions in parallel on the rayon thread pool. The transactions are returned in
+    /// the order of the solutions, and the first error (in that order) is returned if any solution
+    /// fails to encode.
+    #[cfg(feature = "rayon")]
+    pub fn encode_router_calldata_par(
+        &self,
+        solutions: Vec<Solution>,
+    ) -> Result<Vec<Transaction>, EncodingError> {
+        use rayon::prelude::*;
+
+        // Collecting the results first keeps the returned error deterministic
+        let results: Vec<Result<Vec<Transaction>, EncodingError>> = solutions
+            .into_par_iter()
+            .map(|solution| self.encode_solution_transactions(solution))
+            .collect();
+        results
+            .into_iter()
+            .collect::<Result<Vec<Vec<Transaction>>, EncodingError>>()
+            .map(|transactions| {
+                transactions
+                    .into_iter()
+                    .flatten()
+                    .collect()
+            })
+    }
+
+    /// Encodes the solutions into transactions like `TychoEncoder::encode_router_calldata`, and
+    /// returns metrics of the encoding along with them. See `EncodeMeta` for details.
+    pub fn encode_router_calldata_with_meta(
+        &self,
+        solutions: Vec<Solution>,
+    ) -> Result<(Vec<Transaction>, EncodeMeta), EncodingError> {
+        let num_swaps = solutions
+            .iter()
+            .map(|solution| solution.swaps.len())
+            .sum();
+        let start = Instant::now();
+        let transactions = self.encode_router_calldata(solutions)?;
+        let encode_duration = start.elapsed();
+
+        let num_executor_calls = transactions
+            .iter()
+            .map(count_executor_calls)
+            .sum::<Result<usize, EncodingError>>()?;
+        Ok((transactions, EncodeMeta { num_swaps, num_executor_calls, encode_duration }))
+    }
+
+    /// Describes a transaction encoded by this crate: the called method, the executors, token
+    /// indices and splits of the swaps, the amounts and the minimum amount out. See
+    /// `decoder::describe_transaction` for details.
+    pub fn describe(&self, transaction: &Transaction) -> Result<TransactionSummary, EncodingError> {
+        describe_transaction(transaction)
     }
 }
 
 impl EVMTychoEncoder {
+    /// Encodes a single solution into its transactions: the swap, preceded by its approvals if
+    /// `standard_approval` is set.
+    fn encode_solution_transactions(
+        &self,
+        solution: Solution,
+    ) -> Result<Vec<Transaction>, EncodingError> {
+        let mut transactions = if self.standard_approval && !self.eip7702_batch {
+            self.encode_approvals(&solution)?
+        } else {
+            vec![]
+        };
+        transactions.push(self.encode_solution(solution)?);
+        Ok(transactions)
+    }
+
+    /// Validates and encodes a single solution into a transaction.
+    fn encode_solution(&self, solution: Solution) -> Result<Transaction, EncodingError> {
+        let solution = self.with_default_router_address(self.with_native_token(solution));
+        self.validate_solution(&solution)?;
+
+        let method_signature = self
+            .strategy_encoder
+            .method_signature(&solution);
+        trace_debug!(
+            %method_signature,
+            router_address = ?solution.router_address,
+            "Encoding solution"
+        );
+        let (contract_interaction, target_address) = self
+            .strategy_encoder
+            .encode_strategy(solution.clone())?;
+
+        let value = if solution.given_token == self.native_address {
+            solution.given_amount.clone()
+        } else {
+            BigUint::ZERO
+        };
+
+        let transaction = Transaction {
+            value,
+            data: contract_interaction,
+            to: target_address,
+            method: method_signature
+                .split('(')
+                .next()
+                .unwrap_or_default()
+                .to_string(),
+            selector: get_function_selector(&method_signature),
+        };
+        if self.eip7702_batch {
+            self.encode_eip7702_batch(&solution, transaction)
+        } else {
+            Ok(transaction)
+        }
+    }
+
+    /// Packages the approvals of the solution and its swap transaction into an ERC-7821 batch,
+    /// executed by the sender's EIP-7702 delegate.
+    ///
+    /// Only router swaps can be batched: a direct execution is the data of the executor's swap,
+    /// not a call to it, so it is rejected with an `InvalidInput` error.
+    fn encode_eip7702_batch(
+        &self,
+        solution: &Solution,
+        swap: Transaction,
+    ) -> Result<Transaction, EncodingError> {
+        if swap.selector == get_function_selector(DIRECT_EXECUTION_SIGNATURE) {
+            return Err(EncodingError::InvalidInput(
+                "Direct executions can't be encoded as EIP-7702 batches".to_string(),
+            ));
+        }
+        let value = swap.value.clone();
+        let calls = self
+            .encode_approvals(solution)?
+            .into_iter()
+            .chain(std::iter::once(swap))
+            .map(|transaction| {
+                Ok(Call {
+                    to: bytes_to_address(&transaction.to)?,
+                    value: biguint_to_u256(&transaction.value),
+                    data: transaction.data.into(),
+                })
+            })
+            .collect::<Result<Vec<Call>, EncodingError>>()?;
+        let data = encode_input(
+            EIP7702_BATCH_EXECUTE_SIGNATURE,
+            (B256::from(EIP7702_BATCH_MODE), calls.abi_encode()).abi_encode(),
+        );
+        Ok(Transaction {
+            to: solution.sender.clone(),
+            value,
+            data,
+            method: "execute".to_string(),
+            selector: get_function_selector(EIP7702_BATCH_EXECUTE_SIGNATURE),
+        })
+    }
+
     /// Raises an `EncodingError` if the solution is not considered valid.
     ///
     /// A solution is considered valid if all the following conditions are met:
@@ -56,9 +417,7 @@ impl EVMTychoEncoder {
     ///   swap's output is the chain's wrapped token.
     fn validate_solution(&self, solution: &Solution) -> Result<(), EncodingError> {
         if solution.exact_out {
-            return Err(EncodingError::FatalError(
-                "Currently only exact input solutions are supported".to_string(),
-            ));
+            return Err(EncodingError::UnsupportedExactOut);
         }
         if solution.swaps.is_empty() {
             return Err(EncodingError::FatalError("No swaps found in solution".to_string()));
@@ -147,39 +506,72 @@ impl TychoEncoder for EVMTychoEncoder {
         &self,
         solutions: Vec<Solution>,
     ) -> Result<Vec<Transaction>, EncodingError> {
-        let mut transactions: Vec<Transaction> = Vec::new();
-        for solution in solutions.iter() {
-            self.validate_solution(solution)?;
-
-            let (contract_interaction, target_address) = self
-                .strategy_encoder
-                .encode_strategy(solution.clone())?;
-
-            let value = if solution.given_token == self.native_address {
-                solution.given_amount.clone()
-            } else {
-                BigUint::ZERO
-            };
-
-            transactions.push(Transaction {
-                value,
-                data: contract_interaction,
-                to: target_address,
-            });
+        let mut transactions = Vec::with_capacity(solutions.len());
+        for solution in solutions {
+            transactions.extend(self.encode_solution_transactions(solution)?);
         }
         Ok(transactions)
     }
 }
 
+/// Returns the number of executor calls of a transaction encoded by this crate. The calls of an
+/// EIP-7702 batch are counted one by one, so its approvals count as 0 and its swap as the swaps of
+/// the router call. Transactions that don't match a layout of this crate count as 0.
+fn count_executor_calls(transaction: &Transaction) -> Result<usize, EncodingError> {
+    if transaction.selector == get_function_selector(EIP7702_BATCH_EXECUTE_SIGNATURE) {
+        let decode_error = |e: alloy_sol_types::Error| {
+            EncodingError::FatalError(format!("Failed to decode the EIP-7702 batch: {}", e))
+        };
+        let args = transaction
+            .data
+            .get(4..)
+            .unwrap_or_default();
+        let (_mode, execution_data) =
+            <(B256, AlloyBytes)>::abi_decode_params(args, true).map_err(decode_error)?;
+        return Vec::<Call>::abi_decode(&execution_data, true)
+            .map_err(decode_error)?
+            .into_iter()
+            .map(|call| {
+                count_executor_calls(&Transaction {
+                    to: Bytes::from(call.to.to_vec()),
+                    value: BigUint::from_bytes_be(&call.value.to_be_bytes::<32>()),
+                    selector: call
+                        .data
+                        .get(..4)
+                        .and_then(|selector| selector.try_into().ok())
+                        .unwrap_or_default(),
+                    data: call.data.to_vec(),
+                    method: String::new(),
+                })
+            })
+            .sum()
+    }
+    Ok(match describe_transaction(transaction) {
+        Ok(TransactionSummary::TychoRouter(summary)) => summary.swaps.len(),
+        Ok(TransactionSummary::DirectExecution { .. }) => 1,
+        Err(_) => 0,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use std::str::FromStr;
 
+    use rstest::rstest;
     use tycho_core::models::{protocol::ProtocolComponent, Chain as TychoCoreChain};
 
     use super::*;
     use crate::encoding::{
-        models::Swap, strategy_encoder::StrategyEncoder, swap_encoder::SwapEncoder,
+        evm::{
+            constants::SWAP_SIGNATURE,
+            strategy_encoder::strategy_encoders::{
+                AutoStrategyEncoder, ExecutorStrategyEncoder, SplitSwapStrategyEncoder,
+            },
+            swap_encoder::swap_encoder_registry::SwapEncoderRegistry,
+        },
+        models::Swap,
+        strategy_encoder::StrategyEncoder,
+        swap_encoder::SwapEncoder,
     };
 
     fn dai() -> Bytes {
@@ -215,9 +607,28 @@ mod tests {
             ))
         }
 
+        fn method_signature(&self, _solution: &Solution) -> String {
+            "swap(uint256,bytes)".to_string()
+        }
+
+        fn get_approval_spender(
+            &self,
+            solution: &Solution,
+        ) -> Result<Option<Bytes>, EncodingError> {
+            Ok(Some(solution.router_address.clone()))
+        }
+
+        fn estimate_gas(&self, _solution: &Solution) -> Result<u64, EncodingError> {
+            Ok(0)
+        }
+
         fn get_swap_encoder(&self, _protocol_system: &str) -> Option<&Box<dyn SwapEncoder>> {
             None
         }
+
+        fn supported_protocols(&self) -> Vec<String> {
+            vec![]
+        }
         fn clone_box(&self) -> Box<dyn StrategyEncoder> {
             Box::new(self.clone())
         }
@@ -225,7 +636,43 @@ mod tests {
 
     fn get_mocked_tycho_encoder() -> EVMTychoEncoder {
         let strategy_encoder = Box::new(MockStrategy {});
-        EVMTychoEncoder::new(TychoCoreChain::Ethereum, strategy_encoder).unwrap()
+        EVMTychoEncoder::new(TychoCoreChain::Ethereum, strategy_encoder, None).unwrap()
+    }
+
+    /// Strategy that targets the router address of the solution, to check which one is used.
+    #[derive(Clone)]
+    struct RouterTargetStrategy;
+
+    impl StrategyEncoder for RouterTargetStrategy {
+        fn encode_strategy(&self, solution: Solution) -> Result<(Vec<u8>, Bytes), EncodingError> {
+            Ok((vec![], solution.router_address))
+        }
+
+        fn method_signature(&self, _solution: &Solution) -> String {
+            SWAP_SIGNATURE.to_string()
+        }
+
+        fn get_approval_spender(
+            &self,
+            solution: &Solution,
+        ) -> Result<Option<Bytes>, EncodingError> {
+            Ok(Some(solution.router_address.clone()))
+        }
+
+        fn estimate_gas(&self, _solution: &Solution) -> Result<u64, EncodingError> {
+            Ok(0)
+        }
+
+        fn get_swap_encoder(&self, _protocol_system: &str) -> Option<&Box<dyn SwapEncoder>> {
+            None
+        }
+
+        fn supported_protocols(&self) -> Vec<String> {
+            vec![]
+        }
+        fn clone_box(&self) -> Box<dyn StrategyEncoder> {
+            Box::new(self.clone())
+        }
     }
 
     #[test]
@@ -241,6 +688,7 @@ mod tests {
             token_in: weth(),
             token_out: dai(),
             split: 0f64,
+            executor_address: None,
         };
 
         let solution = Solution {
@@ -261,6 +709,524 @@ mod tests {
         assert_eq!(transactions[0].value, eth_amount_in);
         assert_eq!(transactions[0].data, Bytes::from_str("0x1234").unwrap());
         assert_eq!(transactions[0].to, Bytes::from_str("0xabcd").unwrap());
+        assert_eq!(transactions[0].method, "swap");
+        assert_eq!(transactions[0].selector, [0xbd, 0x06, 0x25, 0xab]);
+    }
+
+    #[test]
+    fn test_encode_router_calldata_native_token_sentinel() {
+        let sentinel = Bytes::from_str("0xeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeee").unwrap();
+        let encoder = get_mocked_tycho_encoder().with_native_token_sentinel(sentinel.clone());
+        let eth_amount_in = BigUint::from(1000u32);
+        let swap = Swap {
+            component: ProtocolComponent {
+                id: "0xA478c2975Ab1Ea89e8196811F51A7B7Ade33eB11".to_string(),
+                protocol_system: "uniswap_v2".to_string(),
+                ..Default::default()
+            },
+            token_in: weth(),
+            token_out: dai(),
+            split: 0f64,
+            executor_address: None,
+        };
+        let solution = Solution {
+            given_amount: eth_amount_in.clone(),
+            given_token: sentinel,
+            router_address: Bytes::from_str("0x1234567890abcdef1234567890abcdef12345678").unwrap(),
+            swaps: vec![swap],
+            native_action: Some(NativeAction::Wrap),
+            ..Default::default()
+        };
+
+        let transactions = encoder
+            .encode_router_calldata(vec![solution.clone()])
+            .unwrap();
+        assert_eq!(transactions[0].value, eth_amount_in);
+
+        // Without the sentinel, the given token is not recognized as the native token
+        let result = get_mocked_tycho_encoder().encode_router_calldata(vec![solution]);
+        assert_eq!(
+            result.err(),
+            Some(EncodingError::FatalError(
+                "Native token must be the input token in order to wrap".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_encode_router_calldata_default_router_address() {
+        let default_router = Bytes::from_str("0x3Ede3eCa2a72B3aeCC820E955B36f38437D01395").unwrap();
+        let solution_router =
+            Bytes::from_str("0x1234567890abcdef1234567890abcdef12345678").unwrap();
+        let encoder = EVMTychoEncoder::new(
+            TychoCoreChain::Ethereum,
+            Box::new(RouterTargetStrategy),
+            Some(default_router.clone()),
+        )
+        .unwrap();
+        let swap = Swap {
+            component: ProtocolComponent {
+                id: "0xA478c2975Ab1Ea89e8196811F51A7B7Ade33eB11".to_string(),
+                protocol_system: "uniswap_v2".to_string(),
+                ..Default::default()
+            },
+            token_in: weth(),
+            token_out: dai(),
+            split: 0f64,
+            executor_address: None,
+        };
+        let solution_without_router = Solution {
+            given_token: weth(),
+            checked_token: dai(),
+            swaps: vec![swap],
+            ..Default::default()
+        };
+        let solution_with_router =
+            Solution { router_address: solution_router.clone(), ..solution_without_router.clone() };
+
+        let transactions = encoder
+            .encode_router_calldata(vec![solution_without_router, solution_with_router])
+            .unwrap();
+
+        assert_eq!(transactions[0].to, default_router);
+        assert_eq!(transactions[1].to, solution_router);
+    }
+
+    #[test]
+    #[cfg(feature = "rayon")]
+    fn test_encode_router_calldata_par() {
+        let encoder =
+            EVMTychoEncoder::new(TychoCoreChain::Ethereum, Box::new(RouterTargetStrategy), None)
+                .unwrap();
+        let swap = Swap {
+            component: ProtocolComponent {
+                id: "0xA478c2975Ab1Ea89e8196811F51A7B7Ade33eB11".to_string(),
+                protocol_system: "uniswap_v2".to_string(),
+                ..Default::default()
+            },
+            token_in: weth(),
+            token_out: dai(),
+            split: 0f64,
+            executor_address: None,
+        };
+        let solutions: Vec<Solution> = (0..200u8)
+            .map(|i| Solution {
+                given_token: weth(),
+                checked_token: dai(),
+                swaps: vec![swap.clone()],
+                router_address: Bytes::from(vec![i; 20]),
+                ..Default::default()
+            })
+            .collect();
+
+        let transactions = encoder
+            .encode_router_calldata_par(solutions.clone())
+            .unwrap();
+
+        let expected = encoder
+            .encode_router_calldata(solutions.clone())
+            .unwrap();
+        assert_eq!(
+            transactions
+                .iter()
+                .map(|transaction| transaction.to.clone())
+                .collect::<Vec<_>>(),
+            expected
+                .iter()
+                .map(|transaction| transaction.to.clone())
+                .collect::<Vec<_>>()
+        );
+
+        // The error of the first invalid solution is returned
+        let mut invalid_solutions = solutions;
+        invalid_solutions[30].swaps = vec![];
+        invalid_solutions[60].exact_out = true;
+        let result = encoder.encode_router_calldata_par(invalid_solutions);
+        assert_eq!(
+            result.err(),
+            Some(EncodingError::FatalError("No swaps found in solution".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_calldata_size() {
+        let encoder = get_mocked_tycho_encoder();
+        let solution = Solution {
+            given_amount: BigUint::from(1000u32),
+            given_token: weth(),
+            router_address: Bytes::from_str("0x1234567890abcdef1234567890abcdef12345678").unwrap(),
+            swaps: vec![Swap::new(ProtocolComponent::default(), weth(), dai(), 0f64)],
+            ..Default::default()
+        };
+
+        let size = encoder
+            .calldata_size(&solution)
+            .unwrap();
+
+        // The mocked strategy always encodes 0x1234
+        assert_eq!(size, CalldataSize { total_bytes: 2, zero_bytes: 0, non_zero_bytes: 2 });
+        assert_eq!(size.calldata_gas(), 32);
+    }
+
+    #[test]
+    fn test_encode_router_calldata_with_meta() {
+        let registry = SwapEncoderRegistry::new(None, TychoCoreChain::Ethereum).unwrap();
+        let encoder = EVMTychoEncoder::new(
+            TychoCoreChain::Ethereum,
+            Box::new(ExecutorStrategyEncoder::new(registry)),
+            None,
+        )
+        .unwrap();
+        let swap = Swap {
+            component: ProtocolComponent {
+                id: "0xA478c2975Ab1Ea89e8196811F51A7B7Ade33eB11".to_string(),
+                protocol_system: "uniswap_v2".to_string(),
+                ..Default::default()
+            },
+            token_in: weth(),
+            token_out: dai(),
+            split: 0f64,
+            executor_address: None,
+        };
+        let solution = Solution {
+            given_token: weth(),
+            given_amount: BigUint::from(1000u32),
+            checked_token: dai(),
+            receiver: Bytes::from_str("0xcd09f75E2BF2A4d11F3AB23f1389FcC1621c0cc2").unwrap(),
+            swaps: vec![swap],
+            ..Default::default()
+        };
+
+        let (transactions, meta) = encoder
+            .encode_router_calldata_with_meta(vec![solution.clone(), solution])
+            .unwrap();
+
+        assert_eq!(transactions.len(), 2);
+        assert_eq!(meta.num_swaps, 2);
+        assert_eq!(meta.num_executor_calls, 2);
+    }
+
+    #[test]
+    fn test_encode_router_calldata_with_meta_eip7702_batch() {
+        let registry = SwapEncoderRegistry::new(None, TychoCoreChain::Ethereum).unwrap();
+        let strategy =
+            SplitSwapStrategyEncoder::new(TychoCoreChain::Ethereum, registry, None).unwrap();
+        let encoder = EVMTychoEncoder::new(TychoCoreChain::Ethereum, Box::new(strategy), None)
+            .unwrap()
+            .with_eip7702_batch(true);
+        let swap = Swap {
+            component: ProtocolComponent {
+                id: "0xA478c2975Ab1Ea89e8196811F51A7B7Ade33eB11".to_string(),
+                protocol_system: "uniswap_v2".to_string(),
+                ..Default::default()
+            },
+            token_in: weth(),
+            token_out: dai(),
+            split: 0f64,
+            executor_address: None,
+        };
+        let solution = Solution {
+            sender: Bytes::from_str("0xcd09f75E2BF2A4d11F3AB23f1389FcC1621c0cc2").unwrap(),
+            receiver: Bytes::from_str("0xcd09f75E2BF2A4d11F3AB23f1389FcC1621c0cc2").unwrap(),
+            given_token: weth(),
+            given_amount: BigUint::from(1000u32),
+            checked_token: dai(),
+            checked_amount: Some(BigUint::from(1u32)),
+            router_address: Bytes::from_str("0x3Ede3eCa2a72B3aeCC820E955B36f38437D01395").unwrap(),
+            swaps: vec![swap],
+            ..Default::default()
+        };
+
+        let (transactions, meta) = encoder
+            .encode_router_calldata_with_meta(vec![solution])
+            .unwrap();
+
+        // The batch holds the approval, which isn't counted, and the router call
+        assert_eq!(transactions.len(), 1);
+        assert_eq!(transactions[0].method, "execute");
+        assert_eq!(meta.num_executor_calls, 1);
+    }
+
+    #[test]
+    fn test_encode_router_calldata_standard_approval() {
+        let registry = SwapEncoderRegistry::new(None, TychoCoreChain::Ethereum).unwrap();
+        let strategy =
+            SplitSwapStrategyEncoder::new(TychoCoreChain::Ethereum, registry, None).unwrap();
+        let encoder = EVMTychoEncoder::new(TychoCoreChain::Ethereum, Box::new(strategy), None)
+            .unwrap()
+            .with_standard_approval(true);
+        let router_address = Bytes::from_str("0x3Ede3eCa2a72B3aeCC820E955B36f38437D01395").unwrap();
+        let solution = Solution {
+            sender: Bytes::from_str("0xcd09f75E2BF2A4d11F3AB23f1389FcC1621c0cc2").unwrap(),
+            receiver: Bytes::from_str("0xcd09f75E2BF2A4d11F3AB23f1389FcC1621c0cc2").unwrap(),
+            given_token: weth(),
+            given_amount: BigUint::from(1000u32),
+            checked_token: dai(),
+            checked_amount: Some(BigUint::from(990u32)),
+            router_address: router_address.clone(),
+            swaps: vec![Swap::new(
+                ProtocolComponent {
+                    id: "0xA478c2975Ab1Ea89e8196811F51A7B7Ade33eB11".to_string(),
+                    protocol_system: "uniswap_v2".to_string(),
+                    ..Default::default()
+                },
+                weth(),
+                dai(),
+                0f64,
+            )],
+            ..Default::default()
+        };
+        // Selling the native token needs no approval
+        let native_solution = Solution {
+            given_token: eth(),
+            native_action: Some(NativeAction::Wrap),
+            ..solution.clone()
+        };
+
+        let transactions = encoder
+            .encode_router_calldata(vec![solution, native_solution])
+            .unwrap();
+
+        assert_eq!(transactions.len(), 3);
+        assert_eq!(transactions[0].method, "approve");
+        assert_eq!(transactions[0].to, weth());
+        assert_eq!(
+            transactions[0].data[4..],
+            (bytes_to_address(&router_address).unwrap(), U256::from(1000)).abi_encode()[..]
+        );
+        assert_eq!(transactions[1].method, "swap");
+        assert_eq!(transactions[2].method, "swap");
+        assert_eq!(transactions[2].value, BigUint::from(1000u32));
+    }
+
+    #[test]
+    fn test_encode_approvals() {
+        let encoder = get_mocked_tycho_encoder();
+        let solution = Solution {
+            given_token: dai(),
+            given_amount: BigUint::from(1000u32),
+            router_address: Bytes::from_str("0x3Ede3eCa2a72B3aeCC820E955B36f38437D01395").unwrap(),
+            ..Default::default()
+        };
+
+        let transactions = encoder
+            .encode_approvals(&solution)
+            .unwrap();
+
+        assert_eq!(transactions.len(), 1);
+        assert_eq!(transactions[0].to, dai());
+        assert_eq!(transactions[0].value, BigUint::ZERO);
+        assert_eq!(transactions[0].method, "approve");
+        assert_eq!(
+            hex::encode(&transactions[0].data),
+            String::from(concat!(
+                // approve(address,uint256) selector
+                "095ea7b3",
+                // spender
+                "0000000000000000000000003ede3eca2a72b3aecc820e955b36f38437d01395",
+                // amount
+                "00000000000000000000000000000000000000000000000000000000000003e8"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_encode_router_calldata_eip7702_batch() {
+        let router = Bytes::from_str("0x3Ede3eCa2a72B3aeCC820E955B36f38437D01395").unwrap();
+        let sender = Bytes::from_str("0xcd09f75E2BF2A4d11F3AB23f1389FcC1621c0cc2").unwrap();
+        let encoder =
+            EVMTychoEncoder::new(TychoCoreChain::Ethereum, Box::new(RouterTargetStrategy), None)
+                .unwrap()
+                .with_eip7702_batch(true);
+        let swap = Swap {
+            component: ProtocolComponent {
+                id: "0xA478c2975Ab1Ea89e8196811F51A7B7Ade33eB11".to_string(),
+                protocol_system: "uniswap_v2".to_string(),
+                ..Default::default()
+            },
+            token_in: dai(),
+            token_out: weth(),
+            split: 0f64,
+            executor_address: None,
+        };
+        let solution = Solution {
+            sender: sender.clone(),
+            given_token: dai(),
+            given_amount: BigUint::from(1000u32),
+            checked_token: weth(),
+            swaps: vec![swap],
+            router_address: router.clone(),
+            ..Default::default()
+        };
+
+        let transactions = encoder
+            .encode_router_calldata(vec![solution.clone()])
+            .unwrap();
+
+        assert_eq!(transactions.len(), 1);
+        assert_eq!(transactions[0].to, sender);
+        assert_eq!(transactions[0].method, "execute");
+        assert_eq!(transactions[0].selector, get_function_selector("execute(bytes32,bytes)"));
+        let (mode, execution_data) =
+            <(B256, alloy_primitives::Bytes)>::abi_decode_params(&transactions[0].data[4..], true)
+                .unwrap();
+        assert_eq!(mode, B256::from(EIP7702_BATCH_MODE));
+        let calls = Vec::<Call>::abi_decode(&execution_data, true).unwrap();
+        let approval = encoder
+            .encode_approvals(&solution)
+            .unwrap()
+            .remove(0);
+        assert_eq!(calls.len(), 2);
+        assert_eq!(calls[0].to, bytes_to_address(&dai()).unwrap());
+        assert_eq!(calls[0].value, U256::ZERO);
+        assert_eq!(calls[0].data.to_vec(), approval.data);
+        assert_eq!(calls[1].to, bytes_to_address(&router).unwrap());
+        assert!(calls[1].data.is_empty());
+    }
+
+    #[rstest]
+    #[case::direct_execution(false)]
+    #[case::auto_selects_executor(true)]
+    fn test_encode_router_calldata_eip7702_batch_direct_execution_fails(#[case] auto: bool) {
+        let registry = SwapEncoderRegistry::new(None, TychoCoreChain::Ethereum).unwrap();
+        let strategy: Box<dyn StrategyEncoder> = if auto {
+            Box::new(
+                AutoStrategyEncoder::new(TychoCoreChain::Ethereum, registry, None, None).unwrap(),
+            )
+        } else {
+            Box::new(ExecutorStrategyEncoder::new(registry))
+        };
+        let encoder = EVMTychoEncoder::new(TychoCoreChain::Ethereum, strategy, None)
+            .unwrap()
+            .with_eip7702_batch(true);
+        // A single swap without a minimum amount out, which the auto strategy executes directly
+        let swap = Swap {
+            component: ProtocolComponent {
+                id: "0xA478c2975Ab1Ea89e8196811F51A7B7Ade33eB11".to_string(),
+                protocol_system: "uniswap_v2".to_string(),
+                ..Default::default()
+            },
+            token_in: weth(),
+            token_out: dai(),
+            split: 0f64,
+            executor_address: None,
+        };
+        let solution = Solution {
+            sender: Bytes::from_str("0xcd09f75E2BF2A4d11F3AB23f1389FcC1621c0cc2").unwrap(),
+            given_token: weth(),
+            given_amount: BigUint::from(1000u32),
+            checked_token: dai(),
+            receiver: Bytes::from_str("0xcd09f75E2BF2A4d11F3AB23f1389FcC1621c0cc2").unwrap(),
+            swaps: vec![swap],
+            ..Default::default()
+        };
+
+        let result = encoder.encode_router_calldata(vec![solution]);
+
+        assert_eq!(
+            result.err(),
+            Some(EncodingError::InvalidInput(
+                "Direct executions can't be encoded as EIP-7702 batches".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_encode_approvals_infinite_amount() {
+        let encoder = get_mocked_tycho_encoder().with_approval_amount(ApprovalAmount::Infinite);
+        let solution = Solution {
+            given_token: dai(),
+            given_amount: BigUint::from(1000u32),
+            router_address: Bytes::from_str("0x3Ede3eCa2a72B3aeCC820E955B36f38437D01395").unwrap(),
+            ..Default::default()
+        };
+
+        let transactions = encoder
+            .encode_approvals(&solution)
+            .unwrap();
+
+        assert_eq!(transactions.len(), 1);
+        // The amount is the last argument of the approval
+        assert_eq!(transactions[0].data[4 + 32..], U256::MAX.to_be_bytes::<32>());
+    }
+
+    #[rstest]
+    #[case::no_checked_amount(None, 988)]
+    #[case::checked_amount(Some(995), 995)]
+    fn test_min_amount_out_from_strategy(
+        #[case] checked_amount: Option<u64>,
+        #[case] expected_min_amount_out: u64,
+    ) {
+        let registry = SwapEncoderRegistry::new(None, TychoCoreChain::Ethereum).unwrap();
+        let strategy = SplitSwapStrategyEncoder::new(TychoCoreChain::Ethereum, registry, None)
+            .unwrap()
+            .with_min_out_buffer(U256::from(2u64));
+        let encoder =
+            EVMTychoEncoder::new(TychoCoreChain::Ethereum, Box::new(strategy), None).unwrap();
+        let solution = Solution {
+            expected_amount: Some(BigUint::from(1000u64)),
+            checked_amount: checked_amount.map(BigUint::from),
+            slippage: Some(0.01),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            encoder
+                .min_amount_out(&solution)
+                .unwrap(),
+            BigUint::from(expected_min_amount_out)
+        );
+    }
+
+    #[test]
+    fn test_min_amount_out_without_strategy_check() {
+        // The mocked strategy doesn't check the amount out, so the solution's amounts are used
+        let encoder = get_mocked_tycho_encoder();
+        let solution = Solution {
+            expected_amount: Some(BigUint::from(1000u64)),
+            slippage: Some(0.01),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            encoder
+                .min_amount_out(&solution)
+                .unwrap(),
+            BigUint::from(990u64)
+        );
+    }
+
+    #[test]
+    fn test_min_amount_out_zero_slippage() {
+        let encoder = get_mocked_tycho_encoder();
+        let solution = Solution {
+            expected_amount: Some(BigUint::from(1000u64)),
+            slippage: Some(0.0),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            encoder
+                .min_amount_out(&solution)
+                .unwrap(),
+            BigUint::from(1000u64)
+        );
+    }
+
+    #[rstest]
+    #[case::negative(-0.01)]
+    #[case::above_one(1.5)]
+    fn test_min_amount_out_invalid_slippage(#[case] slippage: f64) {
+        let encoder = get_mocked_tycho_encoder();
+        let solution = Solution {
+            expected_amount: Some(BigUint::from(1000u64)),
+            slippage: Some(slippage),
+            ..Default::default()
+        };
+
+        let result = encoder.min_amount_out(&solution);
+
+        assert!(matches!(result, Err(EncodingError::InvalidInput(_))));
     }
 
     #[test]
@@ -273,11 +1239,11 @@ mod tests {
         let result = encoder.validate_solution(&solution);
 
         assert!(result.is_err());
+        let error = result.err().unwrap();
+        assert_eq!(error, EncodingError::UnsupportedExactOut);
         assert_eq!(
-            result.err().unwrap(),
-            EncodingError::FatalError(
-                "Currently only exact input solutions are supported".to_string()
-            )
+            error.to_string(),
+            "Fatal error: Currently only exact input solutions are supported"
         );
     }
 
@@ -293,6 +1259,7 @@ mod tests {
             token_in: weth(),
             token_out: dai(),
             split: 0f64,
+            executor_address: None,
         };
 
         let solution = Solution {
@@ -322,6 +1289,7 @@ mod tests {
             token_in: weth(),
             token_out: dai(),
             split: 0f64,
+            executor_address: None,
         };
 
         let solution = Solution {
@@ -355,6 +1323,7 @@ mod tests {
             token_in: eth(),
             token_out: dai(),
             split: 0f64,
+            executor_address: None,
         };
 
         let solution = Solution {
@@ -408,6 +1377,7 @@ mod tests {
             token_in: dai(),
             token_out: weth(),
             split: 0f64,
+            executor_address: None,
         };
 
         let solution = Solution {
@@ -436,6 +1406,7 @@ mod tests {
             token_in: dai(),
             token_out: weth(),
             split: 0f64,
+            executor_address: None,
         };
 
         let solution = Solution {
@@ -470,6 +1441,7 @@ mod tests {
             token_in: dai(),
             token_out: eth(),
             split: 0f64,
+            executor_address: None,
         };
 
         let solution = Solution {
@@ -509,6 +1481,7 @@ mod tests {
                 token_in: dai(),
                 token_out: weth(),
                 split: 0.5f64,
+                executor_address: None,
             },
             Swap {
                 component: ProtocolComponent {
@@ -519,6 +1492,7 @@ mod tests {
                 token_in: dai(),
                 token_out: weth(),
                 split: 0f64,
+                executor_address: None,
             },
             Swap {
                 component: ProtocolComponent {
@@ -529,6 +1503,7 @@ mod tests {
                 token_in: weth(),
                 token_out: dai(),
                 split: 0f64,
+                executor_address: None,
             },
         ];
 
@@ -561,6 +1536,7 @@ mod tests {
                 token_in: dai(),
                 token_out: weth(),
                 split: 0f64,
+                executor_address: None,
             },
             Swap {
                 component: ProtocolComponent {
@@ -571,6 +1547,7 @@ mod tests {
                 token_in: weth(),
                 token_out: usdc(),
                 split: 0f64,
+                executor_address: None,
             },
             Swap {
                 component: ProtocolComponent {
@@ -581,6 +1558,7 @@ mod tests {
                 token_in: usdc(),
                 token_out: dai(),
                 split: 0f64,
+                executor_address: None,
             },
             Swap {
                 component: ProtocolComponent {
@@ -591,6 +1569,7 @@ mod tests {
                 token_in: dai(),
                 token_out: wbtc(),
                 split: 0f64,
+                executor_address: None,
             },
         ];
 
@@ -630,6 +1609,7 @@ mod tests {
                 token_in: weth(),
                 token_out: dai(),
                 split: 0f64,
+                executor_address: None,
             },
             Swap {
                 component: ProtocolComponent {
@@ -640,6 +1620,7 @@ mod tests {
                 token_in: dai(),
                 token_out: weth(),
                 split: 0.5f64,
+                executor_address: None,
             },
             Swap {
                 component: ProtocolComponent {
@@ -650,6 +1631,7 @@ mod tests {
                 token_in: dai(),
                 token_out: weth(),
                 split: 0f64,
+                executor_address: None,
             },
         ];
 
@@ -682,6 +1664,7 @@ mod tests {
                 token_in: weth(),
                 token_out: dai(),
                 split: 0f64,
+                executor_address: None,
             },
             Swap {
                 component: ProtocolComponent {
@@ -692,6 +1675,7 @@ mod tests {
                 token_in: dai(),
                 token_out: weth(),
                 split: 0f64,
+                executor_address: None,
             },
         ];
 
@@ -1,4 +1,4 @@
-use std::{cmp::max, sync::Arc};
+use std::sync::Arc;
 
 use alloy_primitives::{aliases::U24, keccak256, Address, FixedBytes, Keccak256, U256, U8};
 use num_bigint::BigUint;
@@ -22,18 +22,29 @@ pub fn bytes_to_address(address: &Bytes) -> Result<Address, EncodingError> {
     }
 }
 
+/// Formats a `Bytes` address as a 0x-prefixed, EIP-55 checksummed string.
+pub fn to_checksum_address(address: &Bytes) -> Result<String, EncodingError> {
+    Ok(bytes_to_address(address)?.to_checksum(None))
+}
+
 /// Converts a general `BigUint` to an EVM-specific `U256` value.
 pub fn biguint_to_u256(value: &BigUint) -> U256 {
     let bytes = value.to_bytes_be();
     U256::from_be_slice(&bytes)
 }
 
+/// Computes the 4 bytes selector of the given function signature.
+pub fn get_function_selector(signature: &str) -> [u8; 4] {
+    let mut hasher = Keccak256::new();
+    hasher.update(signature.as_bytes());
+    let mut selector = [0u8; 4];
+    selector.copy_from_slice(&hasher.finalize()[..4]);
+    selector
+}
+
 /// Encodes the input data for a function call to the given function selector.
 pub fn encode_input(selector: &str, mut encoded_args: Vec<u8>) -> Vec<u8> {
-    let mut hasher = Keccak256::new();
-    hasher.update(selector.as_bytes());
-    let selector_bytes = &hasher.finalize()[..4];
-    let mut call_data = selector_bytes.to_vec();
+    let mut call_data = get_function_selector(selector).to_vec();
     // Remove extra prefix if present (32 bytes for dynamic data)
     // Alloy encoding is including a prefix for dynamic data indicating the offset or length
     // but at this point we don't want that
@@ -61,15 +72,17 @@ pub fn percentage_to_uint24(decimal: f64) -> U24 {
 
 /// Gets the minimum amount out for a solution to pass when executed on-chain.
 ///
-/// The minimum amount is calculated based on the expected amount and the slippage percentage, if
-/// passed. If this information is not passed, the user-passed checked amount will be used.
-/// If both the slippage and minimum user-passed checked amount are passed, the maximum of the two
-/// will be used.
+/// A non-zero user-passed checked amount takes precedence and is used verbatim, ignoring the
+/// slippage. Otherwise, the minimum amount is calculated based on the expected amount and the
+/// slippage percentage, if both are passed.
 /// If neither are passed, the minimum amount will be zero.
 pub fn get_min_amount_for_solution(solution: Solution) -> BigUint {
-    let mut min_amount_out = solution
+    if let Some(checked_amount) = solution
         .checked_amount
-        .unwrap_or(BigUint::ZERO);
+        .filter(|checked_amount| *checked_amount != BigUint::ZERO)
+    {
+        return checked_amount;
+    }
 
     if let (Some(expected_amount), Some(slippage)) =
         (solution.expected_amount.as_ref(), solution.slippage)
@@ -77,10 +90,9 @@ pub fn get_min_amount_for_solution(solution: Solution) -> BigUint {
         let bps = BigUint::from(10_000u32);
         let slippage_percent = BigUint::from((slippage * 10000.0) as u32);
         let multiplier = &bps - slippage_percent;
-        let expected_amount_with_slippage = (expected_amount * &multiplier) / &bps;
-        min_amount_out = max(min_amount_out, expected_amount_with_slippage);
+        return (expected_amount * &multiplier) / &bps;
     }
-    min_amount_out
+    BigUint::ZERO
 }
 
 /// Gets the position of a token in a list of tokens.
@@ -156,4 +168,14 @@ mod tests {
         let min_amount_out = get_min_amount_for_solution(solution);
         assert_eq!(min_amount_out, BigUint::from(999000000000000000u64));
     }
+
+    #[test]
+    fn test_to_checksum_address() {
+        let address = Bytes::from("0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48");
+        assert_eq!(
+            to_checksum_address(&address).unwrap(),
+            "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48"
+        );
+        assert!(to_checksum_address(&Bytes::from("0x1234")).is_err());
+    }
 }
@@ -2,7 +2,9 @@ pub mod errors;
 #[cfg(feature = "evm")]
 pub mod evm;
 pub mod models;
+#[cfg(feature = "schema")]
+pub mod schema;
 pub mod serde_primitives;
 pub mod strategy_encoder;
-mod swap_encoder;
+pub mod swap_encoder;
 pub mod tycho_encoder;
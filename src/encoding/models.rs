@@ -14,37 +14,192 @@ use crate::encoding::{
 /// Represents a solution containing details describing an order, and  instructions for filling
 /// the order.
 #[derive(Clone, Default, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Solution {
     /// Address of the sender.
+    #[cfg_attr(feature = "schema", schemars(with = "crate::encoding::schema::Address"))]
     pub sender: Bytes,
     /// Address of the receiver.
+    #[cfg_attr(feature = "schema", schemars(with = "crate::encoding::schema::Address"))]
     pub receiver: Bytes,
     /// The token being sold (exact in) or bought (exact out).
+    #[cfg_attr(feature = "schema", schemars(with = "crate::encoding::schema::Address"))]
     pub given_token: Bytes,
     /// Amount of the given token.
     #[serde(with = "biguint_string")]
+    #[cfg_attr(feature = "schema", schemars(with = "crate::encoding::schema::Amount"))]
     pub given_amount: BigUint,
     /// The token being bought (exact in) or sold (exact out).
+    #[cfg_attr(feature = "schema", schemars(with = "crate::encoding::schema::Address"))]
     pub checked_token: Bytes,
     /// False if the solution is an exact input solution. Currently only exact input solutions are
     /// supported.
     #[serde(default)]
     pub exact_out: bool,
-    /// If set, it will be applied to expected_amount
+    /// If set, it will be applied to expected_amount to compute the minimum amount out, unless a
+    /// non-zero checked_amount is set.
     pub slippage: Option<f64>,
     /// Expected amount of the bought token (exact in) or sold token (exact out).
     #[serde(with = "biguint_string_option")]
+    #[cfg_attr(feature = "schema", schemars(with = "crate::encoding::schema::NullableAmount"))]
     pub expected_amount: Option<BigUint>,
-    /// Minimum amount to be checked for the solution to be valid.
-    /// If not set, the check will not be performed.
+    /// Minimum amount to be checked for the solution to be valid. If set and non-zero, it's used
+    /// verbatim as the minimum amount out, and expected_amount and slippage are ignored.
     #[serde(with = "biguint_string_option")]
+    #[cfg_attr(feature = "schema", schemars(with = "crate::encoding::schema::NullableAmount"))]
     pub checked_amount: Option<BigUint>,
     /// List of swaps to fulfill the solution.
     pub swaps: Vec<Swap>,
-    /// Address of the router contract to be used for the swaps.
+    /// Address of the router contract to be used for the swaps. If not set, the default router
+    /// address of the encoder is used.
+    #[serde(default)]
+    #[cfg_attr(feature = "schema", schemars(with = "crate::encoding::schema::Address"))]
     pub router_address: Bytes,
     /// If set, the corresponding native action will be executed.
     pub native_action: Option<NativeAction>,
+    /// Nonce of the Permit2 approval. If not set, the current nonce of the sender's Permit2
+    /// allowance is used.
+    pub permit2_nonce: Option<u64>,
+    /// Timestamp (in seconds) until which the Permit2 signature is valid. If not set, the
+    /// signature is valid for 30 minutes.
+    pub permit2_deadline: Option<u64>,
+}
+
+impl Solution {
+    /// Creates an exact in solution that sells `given_amount` of `token_in` for `token_out` with a
+    /// single swap on `component`.
+    ///
+    /// The minimum amount out is `expected_amount` minus the `slippage` (e.g. 0.01 for 1%). All
+    /// other fields have their default values: no native action, and the current Permit2 nonce
+    /// with a 30 minutes deadline.
+    #[allow(clippy::too_many_arguments)]
+    pub fn direct<T: Into<ProtocolComponent>>(
+        sender: Bytes,
+        receiver: Bytes,
+        token_in: Bytes,
+        token_out: Bytes,
+        given_amount: BigUint,
+        expected_amount: BigUint,
+        slippage: f64,
+        component: T,
+        router_address: Bytes,
+    ) -> Self {
+        Self {
+            sender,
+            receiver,
+            given_token: token_in.clone(),
+            given_amount,
+            checked_token: token_out.clone(),
+            exact_out: false,
+            slippage: Some(slippage),
+            expected_amount: Some(expected_amount),
+            checked_amount: None,
+            swaps: vec![Swap::new(component, token_in, token_out, 0f64)],
+            router_address,
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(feature = "evm")]
+impl Solution {
+    /// Checks the solution for structural errors and returns all the errors found, instead of
+    /// stopping at the first one like the encoders do.
+    ///
+    /// The following is checked:
+    /// * The solution is exact in and has at least one swap.
+    /// * The sender, receiver, given token, checked token, swap tokens and executor overrides are
+    ///   20-byte addresses.
+    /// * The router address is set and is a 20-byte address. Solutions that rely on the default
+    ///   router address of the encoder need to set it before being validated.
+    /// * The slippage, if set, is between 0 and 1.
+    /// * The splits are valid, the swap tokens belong to the swaps' components and the swaps form a
+    ///   path from the given token to the checked token.
+    ///
+    /// Passing these checks doesn't guarantee that the solution can be encoded, e.g. a strategy
+    /// may require a checked amount or a lower maximum slippage.
+    pub fn validate(&self, chain: TychoCoreChain) -> Result<(), Vec<EncodingError>> {
+        use crate::encoding::evm::strategy_encoder::strategy_validators::SplitSwapValidator;
+
+        let validator = SplitSwapValidator;
+        let mut errors = vec![];
+        if self.exact_out {
+            errors.push(EncodingError::UnsupportedExactOut);
+        }
+        if self.swaps.is_empty() {
+            errors.push(EncodingError::FatalError("No swaps found in solution".to_string()));
+        }
+
+        for (field, address) in [
+            ("sender", &self.sender),
+            ("receiver", &self.receiver),
+            ("given_token", &self.given_token),
+            ("checked_token", &self.checked_token),
+        ] {
+            if address.len() != 20 {
+                errors.push(EncodingError::InvalidAddress { field });
+            }
+        }
+        if self.router_address.is_empty() {
+            errors.push(EncodingError::InvalidInput("Missing router_address".to_string()));
+        } else if self.router_address.len() != 20 {
+            errors.push(EncodingError::InvalidAddress { field: "router_address" });
+        }
+        for (i, swap) in self.swaps.iter().enumerate() {
+            let executor_address = swap.executor_address.as_ref();
+            for (field, address) in [
+                ("token_in", Some(&swap.token_in)),
+                ("token_out", Some(&swap.token_out)),
+                ("executor_address", executor_address),
+            ] {
+                if address.is_some_and(|address| address.len() != 20) {
+                    errors.push(EncodingError::InvalidInput(format!(
+                        "Invalid {} of swap {}",
+                        field, i
+                    )));
+                }
+            }
+        }
+
+        let checks = [
+            validator.validate_slippage(self.slippage, 1.0),
+            validator.validate_split_percentages(&self.swaps),
+            validator.validate_swap_tokens(&self.swaps),
+        ];
+        errors.extend(
+            checks
+                .into_iter()
+                .filter_map(Result::err),
+        );
+
+        match Chain::from(chain).native_tokens() {
+            // An empty path is already reported above
+            Ok(_) if self.swaps.is_empty() => {}
+            Ok((native_address, wrapped_address)) => {
+                // The strategies infer the native action if it isn't set, so the path is
+                // validated with the inferred one
+                let native_action =
+                    validator.infer_native_action(self, &native_address, &wrapped_address);
+                if let Err(e) = validator.validate_swap_path(
+                    &self.swaps,
+                    &self.given_token,
+                    &self.checked_token,
+                    &native_action,
+                    &native_address,
+                    &wrapped_address,
+                ) {
+                    errors.push(e);
+                }
+            }
+            Err(e) => errors.push(e),
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
 }
 
 /// Represents an action to be performed on the native token either before or after the swap.
@@ -53,6 +208,7 @@ pub struct Solution {
 /// means that the native token will be unwrapped after the last swap, before being sent to the
 /// receiver.
 #[derive(Clone, PartialEq, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "snake_case")]
 pub enum NativeAction {
     Wrap,
@@ -61,16 +217,31 @@ pub enum NativeAction {
 
 /// Represents a swap operation to be performed on a pool.
 #[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Swap {
     /// Protocol component from tycho indexer
+    #[cfg_attr(
+        feature = "schema",
+        schemars(with = "crate::encoding::schema::ProtocolComponentSchema")
+    )]
     pub component: ProtocolComponent,
     /// Token being input into the pool.
+    #[cfg_attr(feature = "schema", schemars(with = "crate::encoding::schema::Address"))]
     pub token_in: Bytes,
     /// Token being output from the pool.
+    #[cfg_attr(feature = "schema", schemars(with = "crate::encoding::schema::Address"))]
     pub token_out: Bytes,
     /// Decimal of the amount to be swapped in this operation (for example, 0.5 means 50%)
     #[serde(default)]
+    #[cfg_attr(feature = "schema", schemars(range(min = 0, max = 1)))]
     pub split: f64,
+    /// Address of the executor to use for this swap. If not set, the executor is resolved by
+    /// protocol system, preferring encoders registered at runtime with
+    /// `SwapEncoderRegistry::register` over the executors config file. If set, it takes precedence
+    /// over both and must be a 20-byte address.
+    #[serde(default)]
+    #[cfg_attr(feature = "schema", schemars(with = "Option<crate::encoding::schema::Address>"))]
+    pub executor_address: Option<Bytes>,
 }
 
 impl Swap {
@@ -80,7 +251,13 @@ impl Swap {
         token_out: Bytes,
         split: f64,
     ) -> Self {
-        Self { component: component.into(), token_in, token_out, split }
+        Self { component: component.into(), token_in, token_out, split, executor_address: None }
+    }
+
+    /// Sets the executor address override of the swap.
+    pub fn with_executor_address(mut self, executor_address: Bytes) -> Self {
+        self.executor_address = Some(executor_address);
+        self
     }
 }
 
@@ -89,13 +266,55 @@ impl Swap {
 /// # Fields
 /// * `to`: Address of the contract to call with the calldata
 /// * `value`: Native token value to be sent with the transaction.
-/// * `data`: Encoded calldata for the transaction.
-/// * `selector`: Only relevant for direct executions. The selector of the function to be called.
+/// * `data`: Encoded calldata for the transaction. For direct executions, this is the protocol data
+///   to pass to the executor's method.
+/// * `method`: Name of the contract method to be called, e.g. `swap`.
+/// * `selector`: The selector of the contract method to be called.
 #[derive(Clone, Debug)]
 pub struct Transaction {
     pub to: Bytes,
     pub value: BigUint,
     pub data: Vec<u8>,
+    pub method: String,
+    pub selector: [u8; 4],
+}
+
+impl Transaction {
+    /// Returns the size of the calldata of the transaction. See `CalldataSize` for details.
+    pub fn calldata_size(&self) -> CalldataSize {
+        let zero_bytes = self
+            .data
+            .iter()
+            .filter(|byte| **byte == 0)
+            .count();
+        CalldataSize {
+            total_bytes: self.data.len(),
+            zero_bytes,
+            non_zero_bytes: self.data.len() - zero_bytes,
+        }
+    }
+}
+
+/// Size of the calldata of a transaction, used to compare the L1 data cost of routes on rollups.
+///
+/// # Fields
+/// * `total_bytes`: Length of the calldata
+/// * `zero_bytes`: Number of zero bytes of the calldata
+/// * `non_zero_bytes`: Number of non-zero bytes of the calldata
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CalldataSize {
+    pub total_bytes: usize,
+    pub zero_bytes: usize,
+    pub non_zero_bytes: usize,
+}
+
+impl CalldataSize {
+    /// Returns the gas charged for the calldata, at 4 gas per zero byte and 16 gas per non-zero
+    /// byte (EIP-2028). Rollups that price their L1 data by these units, e.g. Optimism and Base
+    /// before Ecotone, multiply it by the L1 gas price.
+    pub fn calldata_gas(&self) -> u64 {
+        4 * self.zero_bytes as u64 + 16 * self.non_zero_bytes as u64
+    }
 }
 
 /// Represents necessary attributes for encoding an order.
@@ -141,35 +360,70 @@ impl Chain {
         ))
     }
 
-    pub fn native_token(&self) -> Result<Bytes, EncodingError> {
-        let decode_err_msg = "Failed to decode native token";
+    /// Returns the address of the canonical Permit2 contract of the chain.
+    pub fn permit2_address(&self) -> Result<Bytes, EncodingError> {
         match self.id {
-            1 | 8453 | 42161 => {
-                self.decode_hex("0000000000000000000000000000000000000000", decode_err_msg)
-            }
-            324 => self.decode_hex("000000000000000000000000000000000000800A", decode_err_msg),
+            1 | 8453 | 42161 => self.decode_hex(
+                "000000000022D473030F116dDEE9F6B43aC78BA3",
+                "Failed to decode permit2 address",
+            ),
             _ => Err(EncodingError::InvalidInput(format!(
-                "Native token not set for chain {:?}. Double check the chain is supported.",
+                "Permit2 address not known for chain {:?}. Please set it with \
+                 `EVMEncoderBuilder::permit2_address`.",
                 self.name
             ))),
         }
     }
 
+    /// Returns the native token of the chain, as used in solutions.
+    pub fn native_token(&self) -> Result<Bytes, EncodingError> {
+        Ok(self.native_tokens()?.0)
+    }
+
+    /// Returns the wrapped native token of the chain, i.e. the contract the native token is
+    /// wrapped into and unwrapped from.
     pub fn wrapped_token(&self) -> Result<Bytes, EncodingError> {
-        let decode_err_msg = "Failed to decode wrapped token";
-        match self.id {
-            1 => self.decode_hex("C02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2", decode_err_msg),
-            8453 => self.decode_hex("4200000000000000000000000000000000000006", decode_err_msg),
-            324 => self.decode_hex("5AEa5775959fBC2557Cc8789bC1bf90A239D9a91", decode_err_msg),
-            42161 => self.decode_hex("82aF49447D8a07e3bd95BD0d56f35241523fBab1", decode_err_msg),
-            _ => Err(EncodingError::InvalidInput(format!(
-                "Wrapped token not set for chain {:?}. Double check the chain is supported.",
-                self.name
-            ))),
-        }
+        Ok(self.native_tokens()?.1)
+    }
+
+    /// Returns the `(native_sentinel, wrapped_native_address)` pair of the chain.
+    ///
+    /// The native sentinel is the address that represents the native gas token in solutions, and
+    /// the wrapped native address is the contract it is wrapped into. Both are kept in the same
+    /// table so that a chain whose gas token isn't ETH can't be paired with WETH by mistake.
+    pub fn native_tokens(&self) -> Result<(Bytes, Bytes), EncodingError> {
+        let (native, wrapped) = match self.id {
+            1 => (
+                "0000000000000000000000000000000000000000",
+                "C02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2",
+            ),
+            8453 => (
+                "0000000000000000000000000000000000000000",
+                "4200000000000000000000000000000000000006",
+            ),
+            324 => (
+                "000000000000000000000000000000000000800A",
+                "5AEa5775959fBC2557Cc8789bC1bf90A239D9a91",
+            ),
+            42161 => (
+                "0000000000000000000000000000000000000000",
+                "82aF49447D8a07e3bd95BD0d56f35241523fBab1",
+            ),
+            _ => {
+                return Err(EncodingError::InvalidInput(format!(
+                    "Native token not set for chain {:?}. Double check the chain is supported.",
+                    self.name
+                )))
+            }
+        };
+        Ok((
+            self.decode_hex(native, "Failed to decode native token")?,
+            self.decode_hex(wrapped, "Failed to decode wrapped token")?,
+        ))
     }
 }
 
+#[cfg(test)]
 mod tests {
     use super::*;
 
@@ -207,4 +461,158 @@ mod tests {
         assert_eq!(swap.component.protocol_system, "uniswap_v2");
         assert_eq!(swap.component.id, "i-am-an-id");
     }
+
+    #[test]
+    fn test_swap_executor_address_defaults_to_none() {
+        let swap =
+            Swap::new(ProtocolComponent::default(), Bytes::from("0x12"), Bytes::from("0x34"), 0.0)
+                .with_executor_address(Bytes::from("0x56"));
+        let mut serialized = serde_json::to_value(swap).unwrap();
+        serialized
+            .as_object_mut()
+            .unwrap()
+            .remove("executor_address");
+
+        let deserialized: Swap = serde_json::from_value(serialized).unwrap();
+        assert_eq!(deserialized.executor_address, None);
+    }
+
+    #[test]
+    fn test_solution_direct() {
+        let component = MockProtocolComponent {
+            id: "i-am-an-id".to_string(),
+            protocol_system: "uniswap_v2".to_string(),
+        };
+        let solution = Solution::direct(
+            Bytes::from("0x01"),
+            Bytes::from("0x02"),
+            Bytes::from("0x12"),
+            Bytes::from("0x34"),
+            BigUint::from(1000u32),
+            BigUint::from(990u32),
+            0.01,
+            component,
+            Bytes::from("0x56"),
+        );
+
+        assert_eq!(solution.given_token, Bytes::from("0x12"));
+        assert_eq!(solution.checked_token, Bytes::from("0x34"));
+        assert!(!solution.exact_out);
+        assert_eq!(solution.swaps.len(), 1);
+        assert_eq!(solution.swaps[0].token_in, Bytes::from("0x12"));
+        assert_eq!(solution.swaps[0].token_out, Bytes::from("0x34"));
+        assert_eq!(solution.swaps[0].split, 0f64);
+        assert_eq!(solution.swaps[0].component.id, "i-am-an-id");
+        assert_eq!(solution.router_address, Bytes::from("0x56"));
+    }
+
+    #[test]
+    fn test_transaction_calldata_size() {
+        let transaction = Transaction {
+            to: Bytes::zero(20),
+            value: BigUint::from(0u32),
+            data: vec![0x12, 0x00, 0x00, 0x34, 0x56],
+            method: "swap".to_string(),
+            selector: [0; 4],
+        };
+        let size = transaction.calldata_size();
+
+        assert_eq!(size, CalldataSize { total_bytes: 5, zero_bytes: 2, non_zero_bytes: 3 });
+        assert_eq!(size.calldata_gas(), 2 * 4 + 3 * 16);
+    }
+
+    #[test]
+    fn test_chain_native_tokens() {
+        let zksync = Chain::from(TychoCoreChain::ZkSync);
+        assert_eq!(
+            zksync.native_tokens().unwrap(),
+            (
+                Bytes::from("0x000000000000000000000000000000000000800a"),
+                Bytes::from("0x5aea5775959fbc2557cc8789bc1bf90a239d9a91")
+            )
+        );
+        assert_eq!(zksync.native_token().unwrap(), zksync.native_tokens().unwrap().0);
+        assert_eq!(zksync.wrapped_token().unwrap(), zksync.native_tokens().unwrap().1);
+
+        let base = Chain::from(TychoCoreChain::Base);
+        assert_eq!(
+            base.wrapped_token().unwrap(),
+            Bytes::from("0x4200000000000000000000000000000000000006")
+        );
+
+        let starknet = Chain::from(TychoCoreChain::Starknet);
+        assert!(matches!(starknet.native_tokens(), Err(EncodingError::InvalidInput(_))));
+    }
+
+    #[cfg(feature = "evm")]
+    fn valid_solution() -> Solution {
+        let weth = Bytes::from("0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2");
+        let dai = Bytes::from("0x6b175474e89094c44da98b954eedeac495271d0f");
+        let component = ProtocolComponent {
+            id: "0xA478c2975Ab1Ea89e8196811F51A7B7Ade33eB11".to_string(),
+            protocol_system: "uniswap_v2".to_string(),
+            tokens: vec![weth.clone(), dai.clone()],
+            ..Default::default()
+        };
+        Solution {
+            sender: Bytes::from("0xcd09f75E2BF2A4d11F3AB23f1389FcC1621c0cc2"),
+            receiver: Bytes::from("0xcd09f75E2BF2A4d11F3AB23f1389FcC1621c0cc2"),
+            given_token: weth.clone(),
+            given_amount: BigUint::from(1000u32),
+            checked_token: dai.clone(),
+            checked_amount: Some(BigUint::from(990u32)),
+            router_address: Bytes::from("0x3Ede3eCa2a72B3aeCC820E955B36f38437D01395"),
+            swaps: vec![Swap::new(component, weth, dai, 0f64)],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "evm")]
+    fn test_solution_validate() {
+        assert_eq!(valid_solution().validate(TychoCoreChain::Ethereum), Ok(()));
+    }
+
+    #[test]
+    #[cfg(feature = "evm")]
+    fn test_solution_validate_infers_unwrap() {
+        // The last swap buys WETH while ETH is checked, which the strategies unwrap
+        let weth = Bytes::from("0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2");
+        let dai = Bytes::from("0x6b175474e89094c44da98b954eedeac495271d0f");
+        let solution = valid_solution();
+        let solution = Solution {
+            given_token: dai.clone(),
+            checked_token: Bytes::zero(20),
+            swaps: vec![Swap::new(solution.swaps[0].component.clone(), dai, weth, 0f64)],
+            ..solution
+        };
+
+        assert_eq!(solution.validate(TychoCoreChain::Ethereum), Ok(()));
+    }
+
+    #[test]
+    #[cfg(feature = "evm")]
+    fn test_solution_validate_returns_all_errors() {
+        let mut solution = valid_solution();
+        solution.receiver = Bytes::from("0x1234");
+        solution.router_address = Bytes::default();
+        solution.slippage = Some(1.5);
+        solution.swaps[0].executor_address = Some(Bytes::from("0x56"));
+
+        let errors = solution
+            .validate(TychoCoreChain::Ethereum)
+            .unwrap_err();
+
+        assert_eq!(
+            errors,
+            vec![
+                EncodingError::InvalidAddress { field: "receiver" },
+                EncodingError::InvalidInput("Missing router_address".to_string()),
+                EncodingError::InvalidInput("Invalid executor_address of swap 0".to_string()),
+                EncodingError::InvalidInput(
+                    "Slippage must be between 0 and 1, got 1.5".to_string()
+                ),
+            ]
+        );
+    }
 }
@@ -0,0 +1,198 @@
+use std::{borrow::Cow, collections::HashMap};
+
+use schemars::{json_schema, JsonSchema, Schema, SchemaGenerator};
+use serde_json::Value;
+
+use crate::encoding::models::Solution;
+
+/// Returns the JSON Schema of a `Solution`, as accepted by its `Deserialize` implementation.
+///
+/// Fields are only listed as required if deserialization fails without them. Amounts are decimal
+/// strings and addresses are hex strings.
+pub fn solution_schema() -> Value {
+    schemars::schema_for!(Solution).to_value()
+}
+
+/// Schema of a `Bytes` value, which is (de)serialized as a hex string.
+pub(crate) struct Address;
+
+impl JsonSchema for Address {
+    fn schema_name() -> Cow<'static, str> {
+        "Address".into()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        json_schema!({ "type": "string", "pattern": "^(0x)?[0-9a-fA-F]*$" })
+    }
+}
+
+/// Schema of a `BigUint` amount, which is (de)serialized as a decimal string.
+pub(crate) struct Amount;
+
+impl JsonSchema for Amount {
+    fn schema_name() -> Cow<'static, str> {
+        "Amount".into()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        json_schema!({ "type": "string", "pattern": "^[0-9]+$" })
+    }
+}
+
+/// Schema of an `Option<BigUint>` amount without `#[serde(default)]`: it can be `null` but not
+/// missing.
+pub(crate) struct NullableAmount;
+
+impl JsonSchema for NullableAmount {
+    fn inline_schema() -> bool {
+        true
+    }
+
+    fn schema_name() -> Cow<'static, str> {
+        "NullableAmount".into()
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        generator.subschema_for::<Option<Amount>>()
+    }
+}
+
+// Mirror of tycho-core's `ProtocolComponent`, which doesn't implement `JsonSchema`.
+#[derive(JsonSchema)]
+#[schemars(rename = "ProtocolComponent")]
+#[allow(dead_code)]
+pub(crate) struct ProtocolComponentSchema {
+    id: String,
+    protocol_system: String,
+    protocol_type_name: String,
+    chain: ChainSchema,
+    tokens: Vec<Address>,
+    contract_addresses: Vec<Address>,
+    static_attributes: HashMap<String, Address>,
+    change: ChangeTypeSchema,
+    creation_tx: Address,
+    /// Creation time, e.g. `2024-02-28T12:00:00`
+    created_at: String,
+}
+
+#[derive(JsonSchema)]
+#[schemars(rename = "Chain", rename_all = "lowercase")]
+#[allow(dead_code)]
+enum ChainSchema {
+    Ethereum,
+    Starknet,
+    ZkSync,
+    Arbitrum,
+    Base,
+}
+
+#[derive(JsonSchema)]
+#[schemars(rename = "ChangeType")]
+#[allow(dead_code)]
+enum ChangeTypeSchema {
+    Update,
+    Deletion,
+    Creation,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use num_bigint::BigUint;
+    use tycho_core::{models::protocol::ProtocolComponent, Bytes};
+
+    use super::*;
+    use crate::encoding::models::{NativeAction, Solution, Swap};
+
+    fn property_names(schema: &Value) -> HashSet<String> {
+        schema["properties"]
+            .as_object()
+            .unwrap()
+            .keys()
+            .cloned()
+            .collect()
+    }
+
+    fn key_names(value: &Value) -> HashSet<String> {
+        value
+            .as_object()
+            .unwrap()
+            .keys()
+            .cloned()
+            .collect()
+    }
+
+    fn solution() -> Solution {
+        Solution {
+            given_amount: BigUint::from(1000u32),
+            expected_amount: Some(BigUint::from(990u32)),
+            swaps: vec![Swap::new(
+                ProtocolComponent::default(),
+                Bytes::zero(20),
+                Bytes::zero(20),
+                0f64,
+            )],
+            native_action: Some(NativeAction::Wrap),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_schema_matches_serialized_fields() {
+        let schema = solution_schema();
+        let solution = serde_json::to_value(solution()).unwrap();
+
+        assert_eq!(property_names(&schema), key_names(&solution));
+        assert_eq!(property_names(&schema["$defs"]["Swap"]), key_names(&solution["swaps"][0]));
+        assert_eq!(
+            property_names(&schema["$defs"]["ProtocolComponent"]),
+            key_names(&solution["swaps"][0]["component"])
+        );
+    }
+
+    #[test]
+    fn test_schema_required_fields() {
+        let schema = solution_schema();
+        let mut solution = serde_json::to_value(solution()).unwrap();
+        let required: Vec<String> = schema["required"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|field| field.as_str().unwrap().to_string())
+            .collect();
+        let optional: Vec<String> = property_names(&schema)
+            .into_iter()
+            .filter(|field| !required.contains(field))
+            .collect();
+
+        for field in &optional {
+            solution
+                .as_object_mut()
+                .unwrap()
+                .remove(field);
+        }
+        assert!(serde_json::from_value::<Solution>(solution.clone()).is_ok());
+
+        for field in &required {
+            let mut incomplete = solution.clone();
+            incomplete
+                .as_object_mut()
+                .unwrap()
+                .remove(field);
+            assert!(
+                serde_json::from_value::<Solution>(incomplete).is_err(),
+                "{} should be required",
+                field
+            );
+        }
+    }
+
+    #[test]
+    fn test_solution_serde_round_trip() {
+        let serialized = serde_json::to_value(solution()).unwrap();
+        let deserialized: Solution = serde_json::from_value(serialized.clone()).unwrap();
+
+        assert_eq!(serde_json::to_value(deserialized).unwrap(), serialized);
+    }
+}
@@ -1,9 +1,10 @@
+use num_bigint::BigUint;
 use tycho_core::Bytes;
 
 use crate::encoding::{errors::EncodingError, models::Solution, swap_encoder::SwapEncoder};
 
 /// A trait that defines how to encode a `Solution` for execution.
-pub trait StrategyEncoder {
+pub trait StrategyEncoder: Sync + Send {
     /// `encode_strategy` takes a `Solution`, which contains all the necessary information about
     /// the swaps to be performed, and encodes it into a format that can be executed by the router
     /// or executor contracts.
@@ -19,6 +20,50 @@ pub trait StrategyEncoder {
     ///   - Optionally, the function selector to use when calling the contract
     fn encode_strategy(&self, solution: Solution) -> Result<(Vec<u8>, Bytes), EncodingError>;
 
+    /// Returns the signature of the contract method to call with the encoded `Solution`, e.g.
+    /// `swap(uint256,bytes)` for a direct execution on an executor.
+    ///
+    /// # Arguments
+    /// * `solution` - The `Solution` to be encoded
+    ///
+    /// # Returns
+    /// * `String` - The method signature
+    fn method_signature(&self, solution: &Solution) -> String;
+
+    /// Returns the address that needs an ERC-20 allowance of the given token for the encoded
+    /// `Solution` to be executed, or `None` if no approval is needed.
+    ///
+    /// # Arguments
+    /// * `solution` - The `Solution` to be encoded
+    ///
+    /// # Returns
+    /// * `Result<Option<Bytes>, EncodingError>` - The address to approve, if any
+    fn get_approval_spender(&self, solution: &Solution) -> Result<Option<Bytes>, EncodingError>;
+
+    /// Estimates an upper bound of the gas needed to execute the encoded `Solution`.
+    ///
+    /// The estimate is the sum of the gas constants of every swap's encoder plus the overhead of
+    /// the strategy itself (token transfers, approvals, wrapping, etc.).
+    ///
+    /// # Arguments
+    /// * `solution` - The `Solution` to estimate the gas for
+    ///
+    /// # Returns
+    /// * `Result<u64, EncodingError>` - The gas estimate
+    fn estimate_gas(&self, solution: &Solution) -> Result<u64, EncodingError>;
+
+    /// Returns the minimum amount out that the encoded `Solution` enforces on chain, or `None` if
+    /// the strategy doesn't check the amount out (e.g. a direct execution).
+    ///
+    /// # Arguments
+    /// * `solution` - The `Solution` to be encoded
+    ///
+    /// # Returns
+    /// * `Result<Option<BigUint>, EncodingError>` - The minimum amount out, if it's checked
+    fn min_amount_out(&self, _solution: &Solution) -> Result<Option<BigUint>, EncodingError> {
+        Ok(None)
+    }
+
     /// Retrieves the swap encoder for a specific protocol system.
     ///
     /// # Arguments
@@ -29,6 +74,15 @@ pub trait StrategyEncoder {
     #[allow(clippy::borrowed_box)]
     fn get_swap_encoder(&self, protocol_system: &str) -> Option<&Box<dyn SwapEncoder>>;
 
+    /// Returns the protocol systems that can be encoded by this strategy, sorted by name.
+    fn supported_protocols(&self) -> Vec<String>;
+
     /// Creates a cloned instance of the strategy encoder.
     fn clone_box(&self) -> Box<dyn StrategyEncoder>;
 }
+
+impl Clone for Box<dyn StrategyEncoder> {
+    fn clone(&self) -> Box<dyn StrategyEncoder> {
+        self.clone_box()
+    }
+}
@@ -16,6 +16,9 @@ pub trait SwapEncoder: Sync + Send {
 
     /// Encodes a swap for execution on the protocol.
     ///
+    /// Swaps are encoded without a minimum amount out: the router checks the amount out of the
+    /// whole solution, so a protocol call that takes a per-swap minimum is given 0.
+    ///
     /// # Arguments
     /// * `swap` - The swap details including the protocol component, token in, token out, and split
     /// * `encoding_context` - Additional context needed for encoding (receiver of the tokens,
@@ -32,6 +35,12 @@ pub trait SwapEncoder: Sync + Send {
     /// Returns the address of the protocol-specific executor contract.
     fn executor_address(&self) -> &str;
 
+    /// Returns an upper bound of the gas consumed by a single swap through the executor.
+    ///
+    /// This is a deterministic per-protocol constant and not a simulation, so the real gas usage
+    /// is expected to be lower.
+    fn gas_estimate(&self) -> u64;
+
     /// Creates a cloned instance of the swap encoder.
     ///
     /// This allows the encoder to be cloned when it is being used as a `Box<dyn SwapEncoder>`.
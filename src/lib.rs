@@ -1 +1,13 @@
+/// Emits a `tracing` event at debug level if the `tracing` feature is enabled. The arguments are
+/// not evaluated otherwise.
+#[cfg(feature = "evm")]
+macro_rules! trace_debug {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "tracing")]
+        tracing::debug!($($arg)*);
+    };
+}
+
 pub mod encoding;
+#[cfg(feature = "python")]
+mod python;
@@ -0,0 +1,222 @@
+//! Python bindings of the encoder, built with `maturin build --features python`.
+//!
+//! Exposes a `tycho_execution` module with an `encode_router_calldata` function and an
+//! `EncodingError` exception, raised whenever the encoding fails.
+use std::str::FromStr;
+
+use pyo3::{
+    create_exception,
+    exceptions::{PyException, PyValueError},
+    prelude::*,
+    types::{PyDict, PyList, PyString},
+};
+use tycho_core::models::Chain;
+
+use crate::encoding::{
+    errors,
+    evm::{encoder_builder::EVMEncoderBuilder, utils::to_checksum_address},
+    models::Solution,
+    tycho_encoder::TychoEncoder,
+};
+
+create_exception!(
+    tycho_execution,
+    EncodingError,
+    PyException,
+    "The encoding of a solution failed."
+);
+
+fn to_py_err(err: errors::EncodingError) -> PyErr {
+    EncodingError::new_err(err.to_string())
+}
+
+/// Encodes the solutions into transactions.
+///
+/// # Arguments
+/// * `chain` - Name of the chain, e.g. `ethereum`
+/// * `strategy` - One of `tycho_router`, `tycho_router_permit2` (requires `swapper_pk`),
+///   `direct_execution` or `auto`
+/// * `executors_file_path` - Path to the executors config file. If `None`, the default config is
+///   used
+/// * `solutions` - The solutions to encode, as dicts or JSON strings
+/// * `swapper_pk` - Private key of the swapper, used to sign the Permit2 approval
+///
+/// # Returns
+/// A list of `{"to", "value", "data"}` dicts, in the order of the solutions. All values are hex
+/// strings.
+#[pyfunction]
+#[pyo3(signature = (chain, strategy, executors_file_path, solutions, swapper_pk=None))]
+fn encode_router_calldata<'py>(
+    py: Python<'py>,
+    chain: &str,
+    strategy: &str,
+    executors_file_path: Option<String>,
+    solutions: &Bound<'py, PyList>,
+    swapper_pk: Option<String>,
+) -> PyResult<Bound<'py, PyList>> {
+    let chain = Chain::from_str(chain)
+        .map_err(|_| PyValueError::new_err(format!("Unknown chain: {}", chain)))?;
+
+    let json = PyModule::import(py, "json")?;
+    let solutions = solutions
+        .iter()
+        .map(|solution| {
+            let solution_json: String = match solution.downcast::<PyString>() {
+                Ok(solution_json) => solution_json.to_str()?.to_string(),
+                Err(_) => json
+                    .call_method1("dumps", (solution,))?
+                    .extract()?,
+            };
+            serde_json::from_str(&solution_json)
+                .map_err(|e| PyValueError::new_err(format!("Invalid solution: {}", e)))
+        })
+        .collect::<PyResult<Vec<Solution>>>()?;
+
+    let mut builder = EVMEncoderBuilder::new().chain(chain);
+    if let Some(executors_file_path) = executors_file_path {
+        builder = builder.executors_file_path(executors_file_path);
+    }
+    builder = match strategy {
+        "tycho_router" => builder.initialize_tycho_router(),
+        "tycho_router_permit2" => {
+            let swapper_pk = swapper_pk.ok_or_else(|| {
+                PyValueError::new_err(
+                    "swapper_pk is required for the tycho_router_permit2 strategy",
+                )
+            })?;
+            builder.initialize_tycho_router_with_permit2(swapper_pk)
+        }
+        "direct_execution" => builder.initialize_direct_execution(),
+        "auto" => builder.initialize_auto_strategy(swapper_pk),
+        _ => return Err(PyValueError::new_err(format!("Unknown strategy: {}", strategy))),
+    }
+    .map_err(to_py_err)?;
+    let encoder = builder.build().map_err(to_py_err)?;
+
+    let transactions = encoder
+        .encode_router_calldata(solutions)
+        .map_err(to_py_err)?;
+    let result = PyList::empty(py);
+    for transaction in transactions {
+        let encoded = PyDict::new(py);
+        encoded.set_item("to", to_checksum_address(&transaction.to).map_err(to_py_err)?)?;
+        encoded.set_item("value", format!("0x{}", hex::encode(transaction.value.to_bytes_be())))?;
+        encoded.set_item("data", format!("0x{}", hex::encode(&transaction.data)))?;
+        result.append(encoded)?;
+    }
+    Ok(result)
+}
+
+#[pymodule]
+fn tycho_execution(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(encode_router_calldata, m)?)?;
+    m.add("EncodingError", m.py().get_type::<EncodingError>())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOLUTION_JSON: &str = r#"{
+        "sender": "0xcd09f75E2BF2A4d11F3AB23f1389FcC1621c0cc2",
+        "receiver": "0x1d96f2f6bef1202e4ce1ff6dad0c2cb002861d3e",
+        "given_token": "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2",
+        "given_amount": "1000000000000000000",
+        "checked_token": "0x6b175474e89094c44da98b954eedeac495271d0f",
+        "slippage": null,
+        "expected_amount": null,
+        "checked_amount": "2659881924818443699787",
+        "swaps": [{
+            "component": {
+                "id": "0xA478c2975Ab1Ea89e8196811F51A7B7Ade33eB11",
+                "protocol_system": "uniswap_v2",
+                "protocol_type_name": "",
+                "chain": "ethereum",
+                "tokens": [],
+                "contract_addresses": [],
+                "static_attributes": {},
+                "change": "Update",
+                "creation_tx": "0x",
+                "created_at": "2024-02-28T12:00:00"
+            },
+            "token_in": "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2",
+            "token_out": "0x6b175474e89094c44da98b954eedeac495271d0f",
+            "split": 0.0
+        }],
+        "router_address": "0x3Ede3eCa2a72B3aeCC820E955B36f38437D01395",
+        "native_action": null,
+        "permit2_nonce": null,
+        "permit2_deadline": null
+    }"#;
+
+    /// Encodes the solutions with the Python binding and returns the `to`, `value` and `data` of
+    /// each transaction.
+    fn encode(
+        py: Python<'_>,
+        strategy: &str,
+        solutions: &Bound<'_, PyList>,
+    ) -> PyResult<Vec<(String, String, String)>> {
+        encode_router_calldata(py, "ethereum", strategy, None, solutions, None)?
+            .iter()
+            .map(|transaction| {
+                let field =
+                    |name: &str| -> PyResult<String> { transaction.get_item(name)?.extract() };
+                Ok((field("to")?, field("value")?, field("data")?))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_encode_router_calldata_matches_library() {
+        pyo3::prepare_freethreaded_python();
+        let expected = EVMEncoderBuilder::new()
+            .chain(Chain::Ethereum)
+            .initialize_tycho_router()
+            .unwrap()
+            .build()
+            .unwrap()
+            .encode_router_calldata(vec![serde_json::from_str(SOLUTION_JSON).unwrap()])
+            .unwrap()
+            .remove(0);
+
+        Python::with_gil(|py| {
+            // Solutions can be given as dicts or JSON strings
+            let solution_dict = PyModule::import(py, "json")
+                .unwrap()
+                .call_method1("loads", (SOLUTION_JSON,))
+                .unwrap();
+            let solutions =
+                PyList::new(py, [solution_dict, PyString::new(py, SOLUTION_JSON).into_any()])
+                    .unwrap();
+
+            let transactions = encode(py, "tycho_router", &solutions).unwrap();
+
+            let expected = (
+                to_checksum_address(&expected.to).unwrap(),
+                format!("0x{}", hex::encode(expected.value.to_bytes_be())),
+                format!("0x{}", hex::encode(&expected.data)),
+            );
+            assert_eq!(transactions, vec![expected.clone(), expected]);
+        });
+    }
+
+    #[test]
+    fn test_encode_router_calldata_errors() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let solutions = PyList::new(py, [SOLUTION_JSON]).unwrap();
+            let mut no_swaps: serde_json::Value = serde_json::from_str(SOLUTION_JSON).unwrap();
+            no_swaps["swaps"] = serde_json::json!([]);
+            let invalid_solutions = PyList::new(py, [no_swaps.to_string()]).unwrap();
+
+            let unknown_strategy = encode(py, "unknown", &solutions).unwrap_err();
+            let missing_swapper_pk = encode(py, "tycho_router_permit2", &solutions).unwrap_err();
+            let encoding_error = encode(py, "tycho_router", &invalid_solutions).unwrap_err();
+
+            assert!(unknown_strategy.is_instance_of::<PyValueError>(py));
+            assert!(missing_swapper_pk.is_instance_of::<PyValueError>(py));
+            assert!(encoding_error.is_instance_of::<EncodingError>(py));
+        });
+    }
+}
@@ -0,0 +1,319 @@
+//! Checks that the `tycho-encode` binary outputs the same transactions as the library for the same
+//! solutions, so the CLI serialization (addresses, big-endian value, calldata) can't drift from the
+//! encoder.
+use std::{
+    fs,
+    io::Write,
+    process::{Command, Output, Stdio},
+    str::FromStr,
+};
+
+use alloy::signers::local::PrivateKeySigner;
+use alloy_primitives::{
+    aliases::{U160, U48},
+    Address, PrimitiveSignature as Signature, U256,
+};
+use alloy_sol_types::{eip712_domain, SolStruct};
+use num_bigint::BigUint;
+use tycho_core::{models::Chain, Bytes};
+use tycho_execution::encoding::{
+    evm::{
+        approvals::permit2::{PermitDetails, PermitSingle},
+        decoder::decode_router_calldata,
+        encoder_builder::EVMEncoderBuilder,
+        tycho_encoder::ENCODING_FORMAT_VERSION,
+        utils::to_checksum_address,
+    },
+    models::{Chain as ExecutionChain, Solution, Transaction},
+    tycho_encoder::TychoEncoder,
+};
+
+const WETH: &str = "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2";
+const DAI: &str = "0x6b175474e89094c44da98b954eedeac495271d0f";
+const ETH: &str = "0x0000000000000000000000000000000000000000";
+
+/// A Uniswap V2 swap on the WETH/DAI pool, selling `given_token` for `checked_token`.
+fn solution_json(given_token: &str, checked_token: &str) -> serde_json::Value {
+    serde_json::json!({
+        "sender": "0xcd09f75E2BF2A4d11F3AB23f1389FcC1621c0cc2",
+        "receiver": "0x1d96f2f6bef1202e4ce1ff6dad0c2cb002861d3e",
+        "given_token": given_token,
+        "given_amount": "1000000000000000000",
+        "checked_token": checked_token,
+        "exact_out": false,
+        "slippage": null,
+        "expected_amount": null,
+        "checked_amount": "2659881924818443699787",
+        "swaps": [{
+            "component": {
+                "id": "0xA478c2975Ab1Ea89e8196811F51A7B7Ade33eB11",
+                "protocol_system": "uniswap_v2",
+                "protocol_type_name": "",
+                "chain": "ethereum",
+                "tokens": [WETH, DAI],
+                "contract_addresses": [],
+                "static_attributes": {},
+                "change": "Update",
+                "creation_tx": "0x",
+                "created_at": "2024-02-28T12:00:00"
+            },
+            "token_in": WETH,
+            "token_out": DAI,
+            "split": 0.0
+        }],
+        "router_address": "0x3Ede3eCa2a72B3aeCC820E955B36f38437D01395"
+    })
+}
+
+fn run_cli(input: &serde_json::Value) -> serde_json::Value {
+    serde_json::from_str(&run_cli_with_args(input, &["tycho-router"])).unwrap()
+}
+
+/// Runs the CLI with the given arguments and environment variables, writing the input to stdin.
+fn spawn_cli(input: &serde_json::Value, args: &[&str], envs: &[(&str, &str)]) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_tycho-encode"))
+        .args(args)
+        .env_remove("TYCHO_SWAPPER_PK")
+        .envs(envs.iter().copied())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    // Commands that don't read the input may exit before it's written
+    let _ = child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(input.to_string().as_bytes());
+    child.wait_with_output().unwrap()
+}
+
+/// Runs the CLI with the given arguments and returns its raw stdout.
+fn run_cli_with_args(input: &serde_json::Value, args: &[&str]) -> String {
+    let output = spawn_cli(input, args, &[]);
+    assert!(output.status.success(), "CLI failed: {}", String::from_utf8_lossy(&output.stderr));
+    String::from_utf8(output.stdout).unwrap()
+}
+
+fn encode_with_library(solutions: Vec<Solution>) -> Vec<Transaction> {
+    EVMEncoderBuilder::new()
+        .chain(Chain::Ethereum)
+        .initialize_tycho_router()
+        .unwrap()
+        .build()
+        .unwrap()
+        .encode_router_calldata(solutions)
+        .unwrap()
+}
+
+fn hex_field(output: &serde_json::Value, field: &str) -> Vec<u8> {
+    let value = output[field].as_str().unwrap();
+    hex::decode(value.trim_start_matches("0x")).unwrap()
+}
+
+fn assert_matches(output: &serde_json::Value, transaction: &Transaction) {
+    assert_eq!(output["to"].as_str().unwrap(), to_checksum_address(&transaction.to).unwrap());
+    assert_eq!(Bytes::from_str(output["to"].as_str().unwrap()).unwrap(), transaction.to);
+    assert_eq!(BigUint::from_bytes_be(&hex_field(output, "value")), transaction.value);
+    assert_eq!(hex_field(output, "data"), transaction.data);
+    assert_eq!(hex_field(output, "selector"), transaction.selector);
+    assert_eq!(output["method"].as_str().unwrap(), transaction.method);
+    assert_eq!(
+        output["format_version"]
+            .as_u64()
+            .unwrap(),
+        ENCODING_FORMAT_VERSION as u64
+    );
+}
+
+#[test]
+fn test_cli_matches_library() {
+    let input = solution_json(WETH, DAI);
+
+    let output = run_cli(&input);
+    let transactions = encode_with_library(vec![serde_json::from_value(input).unwrap()]);
+
+    assert_eq!(transactions.len(), 1);
+    assert_matches(&output, &transactions[0]);
+}
+
+#[test]
+fn test_cli_matches_library_with_value() {
+    // Selling ETH wraps it first, so the transaction sends the given amount as value
+    let input = serde_json::Value::Array(vec![solution_json(ETH, DAI), solution_json(WETH, DAI)]);
+
+    let output = run_cli(&input);
+    let transactions = encode_with_library(serde_json::from_value(input).unwrap());
+
+    let output = output.as_array().unwrap();
+    assert_eq!(output.len(), transactions.len());
+    assert_eq!(transactions[0].value, BigUint::from(1_000_000_000_000_000_000u64));
+    for (output, transaction) in output.iter().zip(&transactions) {
+        assert_matches(output, transaction);
+    }
+}
+
+#[test]
+fn test_cli_pretty_output() {
+    let input = solution_json(WETH, DAI);
+
+    let compact = run_cli_with_args(&input, &["tycho-router"]);
+    let pretty = run_cli_with_args(&input, &["tycho-router", "--pretty"]);
+
+    assert_eq!(compact.trim_end().lines().count(), 1);
+    assert!(pretty.trim_end().lines().count() > 1);
+    assert_eq!(
+        serde_json::from_str::<serde_json::Value>(&pretty).unwrap(),
+        serde_json::from_str::<serde_json::Value>(&compact).unwrap()
+    );
+}
+
+/// A solution that can be encoded with Permit2 without an RPC call, since the nonce and deadline
+/// are set.
+fn permit2_solution_json() -> serde_json::Value {
+    let mut input = solution_json(WETH, DAI);
+    input["permit2_nonce"] = serde_json::json!(0);
+    input["permit2_deadline"] = serde_json::json!(u32::MAX);
+    input
+}
+
+#[test]
+fn test_cli_swapper_pk_sources() {
+    let swapper_pk = "0x123456789abcdef123456789abcdef123456789abcdef123456789abcdef1234";
+    let input = permit2_solution_json();
+    let envs = [("RPC_URL", "http://localhost:8545")];
+    let path = std::env::temp_dir().join(format!("tycho-swapper-pk-{}", std::process::id()));
+    fs::write(&path, format!("  {}\n", swapper_pk)).unwrap();
+
+    let from_flag = spawn_cli(&input, &["tycho-router-permit2", "--swapper-pk", swapper_pk], &envs);
+    let from_file = spawn_cli(
+        &input,
+        &["tycho-router-permit2", "--swapper-pk-file", path.to_str().unwrap()],
+        &envs,
+    );
+    let from_env =
+        spawn_cli(&input, &["tycho-router-permit2"], &[envs[0], ("TYCHO_SWAPPER_PK", swapper_pk)]);
+    fs::remove_file(&path).unwrap();
+
+    // The permit expiration depends on the current time, so instead of comparing the calldata, the
+    // permit of each call must be signed by the swapper
+    let swapper = PrivateKeySigner::from_str(swapper_pk)
+        .unwrap()
+        .address();
+    for output in [from_flag, from_file, from_env] {
+        assert!(output.status.success(), "CLI failed: {}", String::from_utf8_lossy(&output.stderr));
+        let output: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        assert_eq!(output["method"], "swapPermit2");
+        assert_eq!(permit_signer(&hex_field(&output, "data")), swapper);
+    }
+}
+
+/// Recovers the signer of the Permit2 approval of a `swapPermit2` call on Ethereum.
+fn permit_signer(calldata: &[u8]) -> Address {
+    let permit = decode_router_calldata(calldata)
+        .unwrap()
+        .permit
+        .unwrap();
+    let permit_single = PermitSingle {
+        details: PermitDetails {
+            token: Address::from_slice(&permit.token),
+            amount: U160::from_be_slice(&permit.amount.to_bytes_be()),
+            expiration: U48::from(permit.expiration),
+            nonce: U48::from(permit.nonce),
+        },
+        spender: Address::from_slice(&permit.spender),
+        sigDeadline: U256::from(permit.sig_deadline),
+    };
+    let chain = ExecutionChain::from(Chain::Ethereum);
+    let domain = eip712_domain! {
+        name: "Permit2",
+        chain_id: chain.id,
+        verifying_contract: Address::from_slice(&chain.permit2_address().unwrap()),
+    };
+    let hash = permit_single.eip712_signing_hash(&domain);
+    Signature::try_from(permit.signature.as_ref())
+        .unwrap()
+        .recover_address_from_prehash(&hash)
+        .unwrap()
+}
+
+#[test]
+fn test_cli_missing_swapper_pk() {
+    let output = spawn_cli(&permit2_solution_json(), &["tycho-router-permit2"], &[]);
+
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("TYCHO_SWAPPER_PK"));
+}
+
+#[test]
+fn test_cli_protocols() {
+    let executors_json = r#"{"ethereum": {
+        "uniswap_v3": "0x1111111111111111111111111111111111111111",
+        "uniswap_v2": "0x2222222222222222222222222222222222222222"
+    }}"#;
+
+    let output = run_cli_with_args(
+        &serde_json::Value::Null,
+        &["--executors-json", executors_json, "protocols"],
+    );
+    let protocols: Vec<String> = serde_json::from_str(&output).unwrap();
+
+    assert_eq!(protocols, vec!["uniswap_v2".to_string(), "uniswap_v3".to_string()]);
+}
+
+#[test]
+fn test_cli_chain_after_subcommand() {
+    let protocols = |args: &[&str]| -> Vec<String> {
+        serde_json::from_str(&run_cli_with_args(&serde_json::Value::Null, args)).unwrap()
+    };
+
+    let before = protocols(&["--chain", "base", "protocols"]);
+    let after = protocols(&["protocols", "--chain", "base"]);
+
+    assert_eq!(after, before);
+    // Base has no Balancer V2 executor, unlike the default chain
+    assert!(!after.contains(&"vm:balancer_v2".to_string()));
+    assert!(protocols(&["protocols"]).contains(&"vm:balancer_v2".to_string()));
+}
+
+#[test]
+fn test_cli_options_after_subcommand() {
+    let path = std::env::temp_dir().join(format!("tycho-solution-{}.json", std::process::id()));
+    fs::write(&path, solution_json(WETH, DAI).to_string()).unwrap();
+    let executors_file_path =
+        concat!(env!("CARGO_MANIFEST_DIR"), "/config/executor_addresses.json");
+    let options = [
+        "--input",
+        path.to_str().unwrap(),
+        "--executors-file-path",
+        executors_file_path,
+        "--estimate-gas",
+        "--no-checksum",
+    ];
+
+    let before = run_cli_with_args(
+        &serde_json::Value::Null,
+        &[options.as_slice(), &["tycho-router"]].concat(),
+    );
+    let after = run_cli_with_args(
+        &serde_json::Value::Null,
+        &[&["tycho-router"], options.as_slice()].concat(),
+    );
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(after, before);
+    let output: serde_json::Value = serde_json::from_str(&after).unwrap();
+    assert!(output["gas_estimate"].is_string());
+    assert_eq!(output["to"], "0x3ede3eca2a72b3aecc820e955b36f38437d01395");
+}
+
+#[test]
+fn test_cli_rejects_too_many_decimals() {
+    let decimals = format!("{}=78", WETH);
+    let output =
+        spawn_cli(&solution_json(WETH, DAI), &["--decimals", &decimals, "tycho-router"], &[]);
+
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Decimals must be at most 77"));
+}